@@ -0,0 +1,117 @@
+use std::time::{Duration, Instant};
+
+use fawkes_crypto::{
+    circuit::eddsaposeidon::c_eddsaposeidon_verify,
+    circuit::num::CNum,
+    circuit::poseidon::{c_poseidon_merkle_proof_root, CMerkleProof},
+    core::cs::{Circuit, ConstraintSystem, WitnessCS, R1CSCS},
+    core::signal::Signal,
+    native::bn256::{Fr, JubJubBN256},
+    native::ecc::JubJubParams,
+    native::eddsaposeidon::eddsaposeidon_sign,
+    native::num::Num,
+    native::poseidon::{MerkleProof, PoseidonParams},
+};
+use rand::{thread_rng, Rng};
+use typenum::U32;
+
+const ITERATIONS: u32 = 30;
+
+struct MerkleProofRoot {
+    poseidon_params: PoseidonParams<Fr>,
+    leaf: Num<Fr>,
+    proof: MerkleProof<Fr, U32>,
+}
+
+impl Circuit for MerkleProofRoot {
+    type F = Fr;
+
+    fn synthesize<CS: ConstraintSystem<F = Fr>>(&self, cs: &CS) {
+        let leaf = CNum::alloc(cs, Some(&self.leaf));
+        let proof = CMerkleProof::alloc(cs, Some(&self.proof));
+        c_poseidon_merkle_proof_root(&leaf, &proof, &self.poseidon_params).inputize();
+    }
+}
+
+struct EddsaPoseidon {
+    poseidon_params: PoseidonParams<Fr>,
+    jubjub_params: JubJubBN256,
+    s: Num<Fr>,
+    r: Num<Fr>,
+    a: Num<Fr>,
+    m: Num<Fr>,
+}
+
+impl Circuit for EddsaPoseidon {
+    type F = Fr;
+
+    fn synthesize<CS: ConstraintSystem<F = Fr>>(&self, cs: &CS) {
+        let s = CNum::alloc(cs, Some(&self.s));
+        let r = CNum::alloc(cs, Some(&self.r));
+        let a = CNum::alloc(cs, Some(&self.a));
+        let m = CNum::alloc(cs, Some(&self.m));
+        m.inputize();
+        c_eddsaposeidon_verify(&s, &r, &a, &m, &self.poseidon_params, &self.jubjub_params)
+            .assert_true();
+    }
+}
+
+// the best of several runs is the least affected by the noise of the machine
+fn measure(f: impl Fn()) -> Duration {
+    (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn bench<C: Circuit<F = Fr>>(name: &str, c: &C) {
+    let witness = measure(|| {
+        WitnessCS::generate(c).unwrap();
+    });
+    let r1cs = measure(|| {
+        let cs = R1CSCS::new();
+        c.synthesize(&cs);
+    });
+    println!(
+        "| {} | {:.2} ms | {:.2} ms |",
+        name,
+        witness.as_secs_f64() * 1000.0,
+        r1cs.as_secs_f64() * 1000.0
+    );
+}
+
+fn main() {
+    let mut rng = thread_rng();
+
+    let merkle = MerkleProofRoot {
+        poseidon_params: PoseidonParams::new(3, 8, 53),
+        leaf: rng.gen(),
+        proof: MerkleProof {
+            sibling: (0..32).map(|_| rng.gen()).collect(),
+            path: (0..32).map(|_| rng.gen()).collect(),
+        },
+    };
+
+    let poseidon_params = PoseidonParams::new(4, 8, 54);
+    let jubjub_params = JubJubBN256::new();
+    let sk = rng.gen();
+    let m = rng.gen();
+    let (s, r) = eddsaposeidon_sign(sk, m, &poseidon_params, &jubjub_params);
+    let a = jubjub_params.edwards_g().mul(sk, &jubjub_params).x;
+    let eddsa = EddsaPoseidon {
+        poseidon_params,
+        jubjub_params,
+        s: s.into_other(),
+        r,
+        a,
+        m,
+    };
+
+    println!("| Circuit | WitnessCS | R1CSCS |\n| - | - | - |");
+    bench("poseidon merkle proof 32", &merkle);
+    bench("poseidon eddsa", &eddsa);
+}
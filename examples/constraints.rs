@@ -0,0 +1,99 @@
+// constraint counts of the README benchmarks, run with `cargo run --example constraints`
+use fawkes_crypto::{
+    circuit::bool::CBool,
+    circuit::ecc::CEdwardsPoint,
+    circuit::eddsaposeidon::c_eddsaposeidon_verify,
+    circuit::keccak::c_keccak_f,
+    circuit::num::CNum,
+    circuit::poseidon::{c_poseidon, c_poseidon_merkle_proof_root, CMerkleProof},
+    circuit::sha256::c_sha256_compress,
+    circuit::uint::{CUint32, CUint64},
+    core::cs::{Circuit, ConstraintSystem},
+    core::profiler::ProfilerCS,
+    core::signal::Signal,
+    native::bn256::{Fr, JubJubBN256},
+    native::ecc::JubJubParams,
+    native::poseidon::PoseidonParams,
+};
+use typenum::U32;
+
+struct Benchmarks {
+    poseidon_params_3: PoseidonParams<Fr>,
+    poseidon_params_4: PoseidonParams<Fr>,
+    jubjub_params: JubJubBN256,
+}
+
+// the inputs are allocated outside of the namespaces, so only the gadgets are counted
+impl Circuit for Benchmarks {
+    type F = Fr;
+
+    fn synthesize<CS: ConstraintSystem<F = Fr>>(&self, cs: &CS) {
+        let nums = (0..4).map(|_| CNum::alloc(cs, None)).collect::<Vec<_>>();
+        let bits = (0..254).map(|_| CBool::alloc(cs, None)).collect::<Vec<_>>();
+        let point = CEdwardsPoint::alloc(cs, None);
+        let g = CEdwardsPoint::from_const(cs, self.jubjub_params.edwards_g());
+        let proof = CMerkleProof::<_, U32>::alloc(cs, None);
+        let sha256_state = (0..8)
+            .map(|_| CUint32::from_bits((0..32).map(|_| CBool::alloc(cs, None)).collect()))
+            .collect::<Vec<_>>();
+        let sha256_block = (0..512).map(|_| CBool::alloc(cs, None)).collect::<Vec<_>>();
+        let keccak_state = (0..25)
+            .map(|_| CUint64::from_bits((0..64).map(|_| CBool::alloc(cs, None)).collect()))
+            .collect::<Vec<_>>();
+
+        {
+            let _ns = cs.namespace("poseidon hash (4, 8, 54), 3 inputs");
+            c_poseidon(&nums[..3], &self.poseidon_params_4);
+        }
+        {
+            let _ns = cs.namespace("jubjub oncurve+subgroup check");
+            point.assert_in_curve(&self.jubjub_params);
+            point.assert_in_subgroup(&self.jubjub_params);
+        }
+        {
+            let _ns = cs.namespace("ecmul_const 254 bits");
+            g.mul(&bits, &self.jubjub_params);
+        }
+        {
+            let _ns = cs.namespace("ecmul 254 bits");
+            point.mul(&bits, &self.jubjub_params);
+        }
+        {
+            let _ns = cs.namespace("poseidon merkle proof 32");
+            c_poseidon_merkle_proof_root(&nums[0], &proof, &self.poseidon_params_3);
+        }
+        {
+            let _ns = cs.namespace("poseidon eddsa");
+            c_eddsaposeidon_verify(
+                &nums[0],
+                &nums[1],
+                &nums[2],
+                &nums[3],
+                &self.poseidon_params_4,
+                &self.jubjub_params,
+            )
+            .assert_true();
+        }
+        {
+            let _ns = cs.namespace("sha256 block");
+            c_sha256_compress(&sha256_state, &sha256_block);
+        }
+        {
+            let _ns = cs.namespace("keccak-f[1600]");
+            c_keccak_f(&keccak_state);
+        }
+    }
+}
+
+fn main() {
+    let profile = ProfilerCS::profile(&Benchmarks {
+        poseidon_params_3: PoseidonParams::new(3, 8, 53),
+        poseidon_params_4: PoseidonParams::new(4, 8, 54),
+        jubjub_params: JubJubBN256::new(),
+    });
+
+    println!("| Circuit | Constraints |\n| - | - |");
+    for c in profile.children.iter() {
+        println!("| {} | {} |", c.name, c.total_constraints());
+    }
+}
@@ -19,4 +19,4 @@ macro_rules! create_derive(
     }
 );
 
-create_derive!(signal, Signal, signal_derive, Value);
+create_derive!(signal, Signal, signal_derive, Value, signal);
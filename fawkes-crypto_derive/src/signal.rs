@@ -1,8 +1,7 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
-    parse_str, Data, DeriveInput, Field, Fields, FieldsNamed, FieldsUnnamed, Ident, Path,
-    PathSegment, Type,
+    parse_str, Data, DeriveInput, Field, Fields, FieldsNamed, FieldsUnnamed, Ident, Member, Type,
 };
 
 fn field_idents<'a>(fields: &'a [&'a Field]) -> Vec<&'a Ident> {
@@ -17,29 +16,48 @@ fn field_idents<'a>(fields: &'a [&'a Field]) -> Vec<&'a Ident> {
 }
 
 fn fetch_attr(name: &str, attrs: &[syn::Attribute]) -> Option<String> {
-    for attr in attrs {
-        if let Ok(meta) = attr.parse_meta() {
-            match meta {
-                syn::Meta::NameValue(nv) => {
-                    if nv.path.get_ident().map(|i| i.to_string()) == Some(name.to_string()) {
-                        match nv.lit {
-                            syn::Lit::Str(ref s) => return Some(s.value()),
-                            _ => {
-                                panic!("attribute {} should be a string", name);
-                            }
-                        }
-                    }
-                }
-                _ => {
-                    panic!("attribute {} should be a string", name);
-                }
-            }
+    for attr in attrs.iter().filter(|a| a.path.is_ident(name)) {
+        match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(nv)) => match nv.lit {
+                syn::Lit::Str(ref s) => return Some(s.value()),
+                _ => panic!("attribute {} should be a string", name),
+            },
+            _ => panic!("attribute {} should be a string", name),
         }
     }
 
     None
 }
 
+// the arguments of #[signal(...)] attributes
+fn signal_args(attrs: &[syn::Attribute]) -> Vec<syn::NestedMeta> {
+    attrs
+        .iter()
+        .filter(|a| a.path.is_ident("signal"))
+        .flat_map(|a| match a.parse_meta() {
+            Ok(syn::Meta::List(l)) => l.nested.into_iter().collect::<Vec<_>>(),
+            _ => panic!("attribute signal should be a list"),
+        })
+        .collect()
+}
+
+fn has_signal_flag(attrs: &[syn::Attribute], name: &str) -> bool {
+    signal_args(attrs).iter().any(|m| match m {
+        syn::NestedMeta::Meta(syn::Meta::Path(p)) => p.is_ident(name),
+        _ => false,
+    })
+}
+
+fn signal_value(attrs: &[syn::Attribute], name: &str) -> Option<String> {
+    signal_args(attrs).iter().find_map(|m| match m {
+        syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident(name) => match nv.lit {
+            syn::Lit::Str(ref s) => Some(s.value()),
+            _ => panic!("signal attribute {} should be a string", name),
+        },
+        _ => None,
+    })
+}
+
 fn unnamed_to_vec(fields: &FieldsUnnamed) -> Vec<&Field> {
     fields.unnamed.iter().collect()
 }
@@ -50,28 +68,62 @@ fn named_to_vec(fields: &FieldsNamed) -> Vec<&Field> {
 
 pub fn expand(input: &DeriveInput, _: &str) -> TokenStream {
     let input_type = &input.ident;
-    let value_type = parse_str::<Type>(
-        &fetch_attr("Value", &input.attrs).expect("attribute value should be defined"),
-    )
-    .expect("attribute should be a type");
+    // the tagged unions are structs with a selector, the variants of a rust enum hold no signals
+    if !matches!(input.data, Data::Struct(_)) {
+        return syn::Error::new_spanned(
+            input_type,
+            "Signal can be derived only for structs, derive a tagged union for a struct with a \
+             #[signal(selector)] field and a #[signal(variant = \"Name\")] field per variant",
+        )
+        .to_compile_error();
+    }
+    let generated_value = signal_value(&input.attrs, "generate_value").map(|name| {
+        assert!(
+            fetch_attr("Value", &input.attrs).is_none(),
+            "attribute Value should not be defined for the generated value"
+        );
+        value_impl(input, &name)
+    });
+    let (value_type, value_struct) = match generated_value {
+        Some(Ok((value_type, value_struct))) => (value_type, value_struct),
+        Some(Err(e)) => return e.to_compile_error(),
+        None => (
+            parse_str::<Type>(
+                &fetch_attr("Value", &input.attrs).expect("attribute value should be defined"),
+            )
+            .expect("attribute should be a type"),
+            quote! {},
+        ),
+    };
 
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
-    let body = match input.data {
+    // the constraint system is taken from the first signal field
+    let fields = match input.data {
         Data::Struct(ref data_struct) => match data_struct.fields {
-            Fields::Unnamed(ref fields) => {
-                let field_vec = unnamed_to_vec(fields);
-                tuple_impl(&field_vec)
-            }
-            Fields::Named(ref fields) => {
-                let field_vec = named_to_vec(fields);
-                struct_impl(&field_vec)
-            }
-            Fields::Unit => struct_impl(&[]),
+            Fields::Unnamed(ref fields) => unnamed_to_vec(fields),
+            Fields::Named(ref fields) => named_to_vec(fields),
+            Fields::Unit => vec![],
         },
-        _ => panic!("Only structs can derive a constructor"),
+        _ => unreachable!(),
+    };
+    if fields
+        .iter()
+        .all(|f| has_signal_flag(&f.attrs, "skip") || has_signal_flag(&f.attrs, "constant"))
+    {
+        return quote! {
+            compile_error!("signal should have at least one signal field, it holds the constraint system");
+        };
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let body = if fields.iter().any(|f| has_signal_flag(&f.attrs, "selector")) {
+        enum_impl(&fields, &value_type)
+    } else {
+        struct_impl(&fields)
     };
 
     quote! {
+        #value_struct
+
         impl #impl_generics Signal<'a, CS> for #input_type#ty_generics #where_clause {
             type Value = #value_type;
 
@@ -81,6 +133,222 @@ pub fn expand(input: &DeriveInput, _: &str) -> TokenStream {
     }
 }
 
+// native type of the signal type, the flag is set if the type depends on the field. The other
+// signals, e.g. CEdwardsPoint or the derived ones, need #[signal(value = "...")] on the field
+fn native_type(t: &Type) -> Result<(TokenStream, bool), syn::Error> {
+    match t {
+        Type::Path(p) => {
+            let segment = p.path.segments.last().unwrap();
+            match segment.ident.to_string().as_str() {
+                "CNum" => Ok((quote! { fawkes_crypto::native::num::Num<F> }, true)),
+                "CBool" => Ok((quote! { bool }, false)),
+                "SizedVec" => match &segment.arguments {
+                    syn::PathArguments::AngleBracketed(args) if args.args.len() == 2 => {
+                        let (item, uses_field) = match &args.args[0] {
+                            syn::GenericArgument::Type(t) => native_type(t)?,
+                            _ => panic!("wrong SizedVec arguments"),
+                        };
+                        let len = &args.args[1];
+                        Ok((
+                            quote! { fawkes_crypto::core::sizedvec::SizedVec<#item, #len> },
+                            uses_field,
+                        ))
+                    }
+                    _ => panic!("wrong SizedVec arguments"),
+                },
+                "CVec" => match &segment.arguments {
+                    syn::PathArguments::AngleBracketed(args) => {
+                        let types = args
+                            .args
+                            .iter()
+                            .filter_map(|a| match a {
+                                syn::GenericArgument::Type(t) => Some(t),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>();
+                        assert!(types.len() == 3, "wrong CVec arguments");
+                        let (item, uses_field) = native_type(types[1])?;
+                        let len = types[2];
+                        Ok((
+                            quote! { fawkes_crypto::core::boundedvec::BoundedVec<#item, #len> },
+                            uses_field,
+                        ))
+                    }
+                    _ => panic!("wrong CVec arguments"),
+                },
+                name => Err(syn::Error::new_spanned(
+                    t,
+                    format!(
+                        "type {} is not supported by generate_value, set its native type with \
+                         #[signal(value = \"...\")]",
+                        name
+                    ),
+                )),
+            }
+        }
+        Type::Tuple(t) => {
+            let (items, uses_field): (Vec<_>, Vec<_>) = t
+                .elems
+                .iter()
+                .map(native_type)
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .unzip();
+            Ok((quote! { (#(#items,)*) }, uses_field.into_iter().any(|f| f)))
+        }
+        Type::Array(t) => {
+            let (item, uses_field) = native_type(&t.elem)?;
+            let len = &t.len;
+            Ok((quote! { [#item; #len] }, uses_field))
+        }
+        _ => Err(syn::Error::new_spanned(
+            t,
+            "type is not supported by generate_value, set its native type with \
+             #[signal(value = \"...\")]",
+        )),
+    }
+}
+
+// the explicit native type may use the field F of the generated value
+fn uses_field(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|t| match t {
+        proc_macro2::TokenTree::Ident(i) => i == "F",
+        proc_macro2::TokenTree::Group(g) => uses_field(g.stream()),
+        _ => false,
+    })
+}
+
+// native struct with the same fields as the signal, returns its type and definition
+fn value_impl(input: &DeriveInput, name: &str) -> Result<(Type, TokenStream), syn::Error> {
+    let vis = &input.vis;
+    let value_ident = Ident::new(name, proc_macro2::Span::call_site());
+    let fields = match input.data {
+        Data::Struct(syn::DataStruct {
+            fields: Fields::Named(ref fields),
+            ..
+        }) => named_to_vec(fields),
+        _ => panic!("generate_value is supported only for structs with named fields"),
+    };
+    let fields = fields
+        .into_iter()
+        .filter(|f| !has_signal_flag(&f.attrs, "skip"))
+        .collect::<Vec<_>>();
+    assert!(
+        !fields.iter().any(|f| has_signal_flag(&f.attrs, "selector")),
+        "generate_value is not supported for signal enums"
+    );
+
+    let var_names = &field_idents(&fields);
+    let var_vis = &fields.iter().map(|f| &f.vis).collect::<Vec<_>>();
+    let types = fields
+        .iter()
+        .map(|f| {
+            if let Some(value) = signal_value(&f.attrs, "value") {
+                let t = parse_str::<Type>(&value).expect("signal attribute value should be a type");
+                let t = quote! { #t };
+                Ok((t.clone(), uses_field(t)))
+            } else if has_signal_flag(&f.attrs, "constant") {
+                let t = &f.ty;
+                Ok((quote! { #t }, false))
+            } else {
+                native_type(&f.ty)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let (var_types, uses_field): (Vec<_>, Vec<_>) = types.into_iter().unzip();
+    let uses_field = uses_field.into_iter().any(|f| f);
+
+    // the generic parameters of the signal except 'a and CS, the field goes first
+    let params = input
+        .generics
+        .type_params()
+        .filter(|p| p.ident != "CS")
+        .collect::<Vec<_>>();
+    let param_idents = params.iter().map(|p| &p.ident).collect::<Vec<_>>();
+    let (field_param, field_arg) = if uses_field {
+        (
+            quote! { F: fawkes_crypto::core::field::Field, },
+            quote! { CS::F, },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+    let field_ident = if uses_field {
+        quote! { F, }
+    } else {
+        quote! {}
+    };
+
+    let value_type =
+        parse_str::<Type>(&quote! { #value_ident<#field_arg #(#param_idents),*> }.to_string())
+            .unwrap();
+
+    // arrays of any length are handled by serde_array and borsh_array helpers
+    let is_array = fields
+        .iter()
+        .map(|f| matches!(f.ty, Type::Array(_)))
+        .collect::<Vec<_>>();
+    let serde_attrs = is_array.iter().map(|&a| {
+        if a {
+            quote! { #[serde(with = "fawkes_crypto::core::array::serde_array")] }
+        } else {
+            quote! {}
+        }
+    });
+    let (borsh_ser, borsh_de): (Vec<_>, Vec<_>) = is_array
+        .iter()
+        .map(|&a| {
+            if a {
+                (
+                    quote! { fawkes_crypto::core::array::borsh_array::serialize },
+                    quote! { fawkes_crypto::core::array::borsh_array::deserialize },
+                )
+            } else {
+                (
+                    quote! { ::borsh::BorshSerialize::serialize },
+                    quote! { ::borsh::BorshDeserialize::deserialize },
+                )
+            }
+        })
+        .unzip();
+
+    // borsh is implemented when the field types implement it, the arrays use borsh_array
+    let borsh_bounds = fields
+        .iter()
+        .zip(var_types.iter())
+        .filter(|(f, _)| !matches!(f.ty, Type::Array(_)))
+        .map(|(_, t)| t)
+        .collect::<Vec<_>>();
+
+    let value_struct = quote! {
+        #[derive(Clone, Debug, ::serde::Serialize, ::serde::Deserialize)]
+        #[serde(bound(serialize = "", deserialize = ""))]
+        #vis struct #value_ident<#field_param #(#params),*> {
+            #(#serde_attrs #var_vis #var_names: #var_types),*
+        }
+
+        impl<#field_param #(#params),*> ::borsh::BorshSerialize for #value_ident<#field_ident #(#param_idents),*>
+        where #(#borsh_bounds: ::borsh::BorshSerialize),*
+        {
+            fn serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+                #(#borsh_ser(&self.#var_names, writer)?;)*
+                Ok(())
+            }
+        }
+
+        impl<#field_param #(#params),*> ::borsh::BorshDeserialize for #value_ident<#field_ident #(#param_idents),*>
+        where #(#borsh_bounds: ::borsh::BorshDeserialize),*
+        {
+            fn deserialize(buf: &mut &[u8]) -> Result<Self, std::io::Error> {
+                Ok(Self {
+                    #(#var_names: #borsh_de(buf)?),*
+                })
+            }
+        }
+    };
+    Ok((value_type, value_struct))
+}
+
 fn get_field_types_iter<'a>(fields: &'a [&'a Field]) -> Box<dyn Iterator<Item = &'a Type> + 'a> {
     Box::new(fields.iter().map(|f| &f.ty))
 }
@@ -89,96 +357,226 @@ fn get_field_types<'a>(fields: &'a [&'a Field]) -> Vec<&'a Type> {
     get_field_types_iter(fields).collect()
 }
 
-fn get_typename(t: &Type) -> &Ident {
-    if let Type::Path(t) = t {
-        let Path {
-            leading_colon: _,
-            segments: t,
-        } = &t.path;
-        let PathSegment {
-            ident: i,
-            arguments: _,
-        } = &t[0];
-        i
-    } else {
-        panic!("wrong type path")
-    }
+// members of the signal struct and the corresponding members of the value, skipped fields are not
+// stored in the value
+fn members(fields: &[&Field]) -> (Vec<Member>, Vec<Option<Member>>) {
+    let mut value_index = 0;
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let member = match f.ident {
+                Some(ref ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(syn::Index::from(i)),
+            };
+            let value_member = if has_signal_flag(&f.attrs, "skip") {
+                None
+            } else {
+                value_index += 1;
+                match f.ident {
+                    Some(ref ident) => Some(Member::Named(ident.clone())),
+                    None => Some(Member::Unnamed(syn::Index::from(value_index - 1))),
+                }
+            };
+            (member, value_member)
+        })
+        .unzip()
 }
 
-fn tuple_impl(fields: &[&Field]) -> TokenStream {
-    let var_typenames = get_field_types(&fields)
-        .iter()
-        .map(|&t| get_typename(t))
+// skipped fields are set to the default values and are not stored in the value, constant fields
+// are plain data copied from the value, they should be equal for the compared and switched signals.
+// alloc without a value sets the constant fields to the defaults, e.g. during the setup, so they
+// should not change the constraints
+fn struct_impl(fields: &[&Field]) -> TokenStream {
+    let (members, value_members) = members(fields);
+    let is_skip = |f: &&Field| has_signal_flag(&f.attrs, "skip");
+    let is_constant = |f: &&Field| has_signal_flag(&f.attrs, "constant");
+    let select = |filter: &dyn Fn(&&Field) -> bool| {
+        let mut res = (vec![], vec![], vec![]);
+        for ((f, m), v) in fields.iter().zip(members.iter()).zip(value_members.iter()) {
+            if filter(f) {
+                res.0.push(m.clone());
+                res.1.push(v.clone());
+                res.2.push(f.ty.clone());
+            }
+        }
+        res
+    };
+
+    let (skips, _, _) = select(&is_skip);
+    let (consts, const_values, _) = select(&is_constant);
+    let (signals, signal_values, signal_types) = select(&|f| !is_skip(f) && !is_constant(f));
+    let const_values = const_values
+        .into_iter()
+        .map(Option::unwrap)
         .collect::<Vec<_>>();
-    let var_ids = (0..fields.len())
-        .map(|i| syn::Index::from(i))
+    let signal_values = signal_values
+        .into_iter()
+        .map(Option::unwrap)
         .collect::<Vec<_>>();
 
+    let first = &signals[0];
+
     quote! {
         fn get_value(&self) -> Option<Self::Value> {
-            Some(Self::Value{#(#var_ids:self.#var_ids.get_value()?),*})
+            Some(Self::Value {
+                #(#signal_values: self.#signals.get_value()?,)*
+                #(#const_values: self.#consts.clone(),)*
+            })
         }
 
         fn as_const(&self) -> Option<Self::Value> {
-            Some(Self::Value{#(#var_ids:self.#var_ids.as_const()?),*})
+            Some(Self::Value {
+                #(#signal_values: self.#signals.as_const()?,)*
+                #(#const_values: self.#consts.clone(),)*
+            })
         }
 
         fn switch(&self, bit: &CBool<'a, CS>, if_else: &Self) -> Self {
-            Self( #(self. #var_ids .switch(bit, &if_else. #var_ids)),* )
+            #(assert!(self.#consts == if_else.#consts, "constant fields should be equal");)*
+            Self {
+                #(#signals: self.#signals.switch(bit, &if_else.#signals),)*
+                #(#skips: self.#skips.clone(),)*
+                #(#consts: self.#consts.clone(),)*
+            }
         }
 
         fn get_cs(&self) -> &'a CS {
-            self.0.get_cs()
+            self.#first.get_cs()
         }
 
         fn from_const(cs:&'a CS, value: &Self::Value) -> Self {
-            Self(#(#var_typenames::from_const(cs, &value.#var_ids)),*)
+            Self {
+                #(#signals: <#signal_types as Signal<'a, CS>>::from_const(cs, &value.#signal_values),)*
+                #(#skips: Default::default(),)*
+                #(#consts: value.#const_values.clone(),)*
+            }
         }
 
         fn assert_const(&self, value: &Self::Value) {
-            #(self. #var_ids .assert_const(&value. #var_ids);)*
+            #(assert!(self.#consts == value.#const_values, "constant fields should be equal");)*
+            #(self.#signals.assert_const(&value.#signal_values);)*
         }
 
         fn inputize(&self) {
-            #(self. #var_ids .inputize();)*
+            #(self.#signals.inputize();)*
         }
 
         fn linearize_builder(&self, acc:&mut Vec<CNum<'a, CS>>) {
-            #(self. #var_ids .linearize_builder(acc);)*
+            #(self.#signals.linearize_builder(acc);)*
         }
 
         fn assert_eq(&self, other: &Self) {
-            #(self. #var_ids .assert_eq(&other. #var_ids);)*
+            #(assert!(self.#consts == other.#consts, "constant fields should be equal");)*
+            #(self.#signals.assert_eq(&other.#signals);)*
         }
 
         fn is_eq(&self, other: &Self) -> CBool<'a, CS> {
+            #(assert!(self.#consts == other.#consts, "constant fields should be equal");)*
             let mut acc = CNum::one(self.get_cs());
-            #(acc *= self. #var_ids .is_eq(&other. #var_ids).0;)*
+            #(acc *= self.#signals.is_eq(&other.#signals).0;)*
             acc.into_bool()
         }
 
         fn alloc(cs:&'a CS, value:Option<&Self::Value>) -> Self {
-            Self(#(#var_typenames::alloc(cs, value.map(|v| &v.#var_ids))),*)
+            Self {
+                #(#signals: <#signal_types as Signal<'a, CS>>::alloc(cs, value.map(|v| &v.#signal_values)),)*
+                #(#skips: Default::default(),)*
+                #(#consts: value.map(|v| v.#const_values.clone()).unwrap_or_default(),)*
+            }
         }
     }
 }
 
-fn struct_impl(fields: &[&Field]) -> TokenStream {
+// a tagged union: the selector holds the index of the variant, every variant has its own payload
+// field, the payloads of the inactive variants are set to the default values
+fn enum_impl(fields: &[&Field], value_type: &Type) -> TokenStream {
+    let mut value_path = match value_type {
+        Type::Path(t) => t.path.clone(),
+        _ => panic!("wrong type path"),
+    };
+    value_path.segments.last_mut().unwrap().arguments = syn::PathArguments::None;
+
     let var_names: &Vec<Ident> = &field_idents(fields).iter().map(|f| (**f).clone()).collect();
+    let var_types = get_field_types(&fields);
 
-    let var_name_first = var_names[0].clone();
-    let var_typenames = get_field_types(&fields)
+    let selector_fields = fields
+        .iter()
+        .filter(|f| has_signal_flag(&f.attrs, "selector"))
+        .collect::<Vec<_>>();
+    assert!(
+        selector_fields.len() == 1,
+        "signal enum should have exactly one selector"
+    );
+    let selector = selector_fields[0].ident.as_ref().unwrap();
+    let selector_type = &selector_fields[0].ty;
+
+    let variant_fields = fields
+        .iter()
+        .filter(|f| !has_signal_flag(&f.attrs, "selector"))
+        .map(|f| {
+            let variant = signal_value(&f.attrs, "variant")
+                .expect("every field of signal enum should be a selector or a variant");
+            (f, Ident::new(&variant, proc_macro2::Span::call_site()))
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        !variant_fields.is_empty(),
+        "signal enum should have at least one variant"
+    );
+    let payloads = &variant_fields
         .iter()
-        .map(|&t| get_typename(t))
+        .map(|(f, _)| f.ident.as_ref().unwrap())
+        .collect::<Vec<_>>();
+    let variants = &variant_fields.iter().map(|(_, v)| v).collect::<Vec<_>>();
+    let indexes = &(0..variant_fields.len() as u64).collect::<Vec<_>>();
+    let nvariants = variant_fields.len() as u64;
+
+    let index_value = quote! { <#selector_type as Signal<'a, CS>>::Value::from };
+
+    // the value of every field for the given native value
+    let field_values = var_names
+        .iter()
+        .map(|name| {
+            if name == selector {
+                quote! {
+                    match value {
+                        #(#value_path::#variants(_) => #index_value(#indexes),)*
+                    }
+                }
+            } else {
+                let variant = variant_fields
+                    .iter()
+                    .find(|(f, _)| f.ident.as_ref() == Some(name))
+                    .unwrap()
+                    .1
+                    .clone();
+                quote! {{
+                    #[allow(unreachable_patterns)]
+                    match value {
+                        #value_path::#variant(p) => p.clone(),
+                        _ => Default::default(),
+                    }
+                }}
+            }
+        })
         .collect::<Vec<_>>();
 
     quote! {
         fn get_value(&self) -> Option<Self::Value> {
-            Some(Self::Value {#(#var_names: self.#var_names.get_value()?),*})
+            let index = self.#selector.get_value()?;
+            #(if index == #index_value(#indexes) {
+                return Some(#value_path::#variants(self.#payloads.get_value()?));
+            })*
+            None
         }
 
         fn as_const(&self) -> Option<Self::Value> {
-            Some(Self::Value {#(#var_names: self.#var_names.as_const()?),*})
+            let index = self.#selector.as_const()?;
+            #(if index == #index_value(#indexes) {
+                return Some(#value_path::#variants(self.#payloads.as_const()?));
+            })*
+            None
         }
 
         fn switch(&self, bit: &CBool<'a, CS>, if_else: &Self) -> Self {
@@ -186,15 +584,20 @@ fn struct_impl(fields: &[&Field]) -> TokenStream {
         }
 
         fn get_cs(&self) -> &'a CS {
-            self.#var_name_first.get_cs()
+            self.#selector.get_cs()
         }
 
         fn from_const(cs:&'a CS, value: &Self::Value) -> Self {
-            Self {#(#var_names: #var_typenames::from_const(cs, &value.#var_names)),*}
+            Self {#(#var_names: <#var_types as Signal<'a, CS>>::from_const(cs, &#field_values)),*}
         }
 
         fn assert_const(&self, value: &Self::Value) {
-            #(self. #var_names .assert_const(&value. #var_names);)*
+            match value {
+                #(#value_path::#variants(p) => {
+                    self.#selector.assert_const(&#index_value(#indexes));
+                    self.#payloads.assert_const(p);
+                })*
+            }
         }
 
         fn inputize(&self) {
@@ -206,17 +609,31 @@ fn struct_impl(fields: &[&Field]) -> TokenStream {
         }
 
         fn assert_eq(&self, other: &Self) {
-            #(self. #var_names .assert_eq(&other. #var_names);)*
+            self.is_eq(other).assert_true();
         }
 
+        // the payloads of the inactive variants are not compared
         fn is_eq(&self, other: &Self) -> CBool<'a, CS> {
-            let mut acc = CNum::one(self.get_cs());
-            #(acc *= self. #var_names .is_eq(&other. #var_names).0;)*
-            acc.into_bool()
+            let mut acc = CNum::zero(self.get_cs());
+            #(
+                let index = self.#selector.derive_const::<#selector_type>(&#index_value(#indexes));
+                acc += self.#selector.is_eq(&index).0 * self.#payloads.is_eq(&other.#payloads).0;
+            )*
+            (self.#selector.is_eq(&other.#selector).0 * acc).into_bool()
         }
 
+        // the selector is checked to be the index of one of the variants
         fn alloc(cs:&'a CS, value:Option<&Self::Value>) -> Self {
-            Self {#(#var_names: #var_typenames::alloc(cs, value.map(|v| &v.#var_names))),*}
+            let res = Self {#(#var_names: <#var_types as Signal<'a, CS>>::alloc(
+                cs,
+                value.map(|value| #field_values).as_ref()
+            )),*};
+            let mut acc = res.#selector.clone();
+            for i in 1..#nvariants {
+                acc *= &res.#selector - #index_value(i);
+            }
+            acc.assert_zero();
+            res
         }
     }
 }
@@ -0,0 +1,78 @@
+use crate::{
+    circuit::bool::CBool, circuit::mux::c_select, circuit::num::CNum, core::cs::ConstraintSystem,
+    core::signal::Signal, native::num::Num,
+};
+
+// the flags of the items selected by the index, exactly one of them should be set
+fn index_flags<'a, CS: ConstraintSystem>(index: &CNum<'a, CS>, len: usize) -> Vec<CBool<'a, CS>> {
+    assert!(len > 0, "should be at least one item");
+    let flags = (0..len)
+        .map(|i| index.is_eq(&index.derive_const(&Num::from(i as u64))))
+        .collect::<Vec<_>>();
+    flags
+        .iter()
+        .fold(index.derive_zero(), |acc, f| acc + &f.0)
+        .assert_const(&Num::one());
+    flags
+}
+
+// the index is checked to be less than the number of items
+pub fn c_array_get<'a, CS: ConstraintSystem, T: Signal<'a, CS>>(
+    items: &[T],
+    index: &CNum<'a, CS>,
+) -> T {
+    let _ns = index.get_cs().namespace("array_get");
+    c_select(index, items)
+}
+
+// returns the items with items[index] replaced by the value
+pub fn c_array_set<'a, CS: ConstraintSystem, T: Signal<'a, CS>>(
+    items: &[T],
+    index: &CNum<'a, CS>,
+    value: &T,
+) -> Vec<T> {
+    let _ns = index.get_cs().namespace("array_set");
+    let flags = index_flags(index, items.len());
+    flags
+        .iter()
+        .zip(items.iter())
+        .map(|(f, item)| f.if_else(value, item))
+        .collect()
+}
+
+#[cfg(test)]
+mod array_test {
+    use bellman::pairing::bn256::Fr;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::core::cs::TestCS;
+
+    #[test]
+    fn test_array_get_set() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new_collecting();
+        let values = (0..6).map(|_| rng.gen()).collect::<Vec<Num<Fr>>>();
+        let items = values
+            .iter()
+            .map(|v| CNum::alloc(cs, Some(v)))
+            .collect::<Vec<_>>();
+        let value = rng.gen();
+        let signal_value = CNum::alloc(cs, Some(&value));
+
+        for (i, v) in values.iter().enumerate() {
+            let index = CNum::alloc(cs, Some(&Num::from(i as u64)));
+            assert!(c_array_get(&items, &index).get_value() == Some(*v));
+
+            let res = c_array_set(&items, &index, &signal_value);
+            let mut expected = values.clone();
+            expected[i] = value;
+            assert!(res.iter().map(|r| r.get_value().unwrap()).eq(expected));
+        }
+        assert!(cs.check_satisfied().is_ok());
+
+        let index = CNum::alloc(cs, Some(&num!(6)));
+        c_array_get(&items, &index);
+        assert!(cs.check_satisfied().is_err());
+    }
+}
@@ -7,6 +7,7 @@ pub fn c_into_bits_le<'a, CS: ConstraintSystem>(
     signal: &CNum<'a, CS>,
     limit: usize,
 ) -> Vec<CBool<'a, CS>> {
+    let _ns = signal.get_cs().namespace("into_bits_le");
     match signal.as_const() {
         Some(value) => {
             let mut bits = Vec::<CBool<'a, CS>>::new();
@@ -57,6 +58,7 @@ pub fn c_comp_constant<'a, CS: ConstraintSystem>(
     let siglen = signal.len();
     assert!(siglen > 0, "should be at least one input signal");
     let cs = signal[0].get_cs();
+    let _ns = cs.namespace("comp_constant");
     let nsteps = (siglen >> 1) + (siglen & 1);
     let sig_zero = if siglen & 1 == 1 {
         vec![CBool::c_false(cs)]
@@ -115,3 +117,195 @@ pub fn c_from_bits_le<'a, CS: ConstraintSystem>(bits: &[CBool<'a, CS>]) -> CNum<
     }
     acc
 }
+
+// return true if a > b, the same pairwise technique as c_comp_constant: every pair of bits
+// gives the sign of the pairs difference, costs 2 constraints per pair and the decomposition
+// of the accumulator
+pub fn c_comp<'a, CS: ConstraintSystem>(a: &[CBool<'a, CS>], b: &[CBool<'a, CS>]) -> CBool<'a, CS> {
+    let siglen = a.len();
+    assert!(siglen > 0, "should be at least one input signal");
+    assert!(siglen == b.len(), "signals should have the same length");
+    let cs = a[0].get_cs();
+    let _ns = cs.namespace("comp");
+    let nsteps = (siglen >> 1) + (siglen & 1);
+    let sig_zero = if siglen & 1 == 1 {
+        vec![CBool::c_false(cs)]
+    } else {
+        vec![]
+    };
+
+    let mut a_bits = a.iter().chain(sig_zero.iter());
+    let mut b_bits = b.iter().chain(sig_zero.iter());
+
+    let mut k = Num::one();
+    let mut acc = CNum::zero(cs);
+
+    for _ in 0..nsteps {
+        let (a_l, a_u) = (a_bits.next().unwrap(), a_bits.next().unwrap());
+        let (b_l, b_u) = (b_bits.next().unwrap(), b_bits.next().unwrap());
+
+        // d_u^2 = a_u xor b_u, the lower bits decide if the upper bits are equal
+        let d_u = &a_u.0 - &b_u.0;
+        let d_l = &a_l.0 - &b_l.0;
+        let eq_u = &a_u.0 * &b_u.0 * num!(2) - &a_u.0 - &b_u.0 + Num::one();
+
+        acc += k * (d_u + eq_u * d_l);
+        k = k.double();
+    }
+
+    k -= Num::one();
+
+    acc += k;
+    let acc_bits = c_into_bits_le(&acc, nsteps + 1);
+    acc_bits[nsteps].clone()
+}
+
+// return true if a < b, both a and b are decomposed into `bits` bits (c_into_bits_le), which
+// enforces the range, the circuit is unsatisfiable for the wider values
+pub fn c_lt<'a, CS: ConstraintSystem>(
+    a: &CNum<'a, CS>,
+    b: &CNum<'a, CS>,
+    bits: usize,
+) -> CBool<'a, CS> {
+    assert!(
+        bits < CS::F::NUM_BITS as usize,
+        "number of bits should be less than the field size"
+    );
+    let _ns = a.get_cs().namespace("lt");
+    c_comp(&c_into_bits_le(b, bits), &c_into_bits_le(a, bits))
+}
+
+#[inline]
+pub fn c_le<'a, CS: ConstraintSystem>(
+    a: &CNum<'a, CS>,
+    b: &CNum<'a, CS>,
+    bits: usize,
+) -> CBool<'a, CS> {
+    c_lt(b, a, bits).not()
+}
+
+#[inline]
+pub fn c_gt<'a, CS: ConstraintSystem>(
+    a: &CNum<'a, CS>,
+    b: &CNum<'a, CS>,
+    bits: usize,
+) -> CBool<'a, CS> {
+    c_lt(b, a, bits)
+}
+
+#[inline]
+pub fn c_ge<'a, CS: ConstraintSystem>(
+    a: &CNum<'a, CS>,
+    b: &CNum<'a, CS>,
+    bits: usize,
+) -> CBool<'a, CS> {
+    c_lt(a, b, bits).not()
+}
+
+// the signal should be less than 2^bits
+pub fn c_assert_range<'a, CS: ConstraintSystem>(signal: &CNum<'a, CS>, bits: usize) {
+    let _ns = signal.get_cs().namespace("assert_range");
+    c_into_bits_le(signal, bits);
+}
+
+// return true if lo <= signal <= hi, works for any field element
+pub fn c_in_range<'a, CS: ConstraintSystem>(
+    signal: &CNum<'a, CS>,
+    lo: Num<CS::F>,
+    hi: Num<CS::F>,
+) -> CBool<'a, CS> {
+    let _ns = signal.get_cs().namespace("in_range");
+    let bits = c_into_bits_le_strict(signal);
+    let le_hi = c_comp_constant(&bits, hi).not();
+    if lo.is_zero() {
+        le_hi
+    } else {
+        le_hi.and(&c_comp_constant(&bits, lo - Num::one()))
+    }
+}
+
+#[cfg(test)]
+mod bitify_test {
+    use bellman::pairing::bn256::Fr;
+    use num::bigint::BigUint;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::core::cs::TestCS;
+
+    fn rand_num<R: Rng>(rng: &mut R, bits: usize) -> Num<Fr> {
+        let bytes = (0..bits / 8).map(|_| rng.gen()).collect::<Vec<u8>>();
+        Num::from(BigUint::from_bytes_le(&bytes))
+    }
+
+    #[test]
+    fn test_comparison() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        for &bits in [8, 64, 200].iter() {
+            for i in 0..10 {
+                let x = rand_num(&mut rng, bits);
+                let y = if i == 0 { x } else { rand_num(&mut rng, bits) };
+                let (bx, by): (BigUint, BigUint) = (x.into(), y.into());
+                let a = CNum::alloc(cs, Some(&x));
+                let b = CNum::alloc(cs, Some(&y));
+
+                assert!(c_lt(&a, &b, bits).get_value() == Some(bx < by));
+                assert!(c_le(&a, &b, bits).get_value() == Some(bx <= by));
+                assert!(c_gt(&a, &b, bits).get_value() == Some(bx > by));
+                assert!(c_ge(&a, &b, bits).get_value() == Some(bx >= by));
+
+                let c = CNum::from_const(cs, &y);
+                assert!(c_lt(&a, &c, bits).get_value() == Some(bx < by));
+                assert!(c_lt(&c, &c, bits).as_const() == Some(false));
+            }
+        }
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_comparison_odd_bits() {
+        let cs = &TestCS::<Fr>::new();
+        for x in 0..8u64 {
+            for y in 0..8u64 {
+                let a = CNum::alloc(cs, Some(&Num::from(x)));
+                let b = CNum::alloc(cs, Some(&Num::from(y)));
+                assert!(c_lt(&a, &b, 3).get_value() == Some(x < y));
+            }
+        }
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_range() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new_collecting();
+        let lo = rand_num(&mut rng, 64);
+        let hi = lo + rand_num(&mut rng, 64);
+        let (blo, bhi): (BigUint, BigUint) = (lo.into(), hi.into());
+        let values = [
+            Num::zero(),
+            lo,
+            lo - Num::one(),
+            hi,
+            hi + Num::one(),
+            lo + rand_num(&mut rng, 32),
+            -Num::one(),
+        ];
+        for v in values.iter() {
+            let bv: BigUint = (*v).into();
+            let signal = CNum::alloc(cs, Some(v));
+            let res = c_in_range(&signal, lo, hi);
+            assert!(res.get_value() == Some(blo <= bv && bv <= bhi));
+        }
+        assert!(c_in_range(&CNum::alloc(cs, Some(&lo)), Num::zero(), lo).get_value() == Some(true));
+        assert!(cs.check_satisfied().is_ok());
+
+        let x = CNum::alloc(cs, Some(&num!(255)));
+        c_assert_range(&x, 8);
+        assert!(cs.check_satisfied().is_ok());
+        let x = CNum::alloc(cs, Some(&num!(256)));
+        c_assert_range(&x, 8);
+        assert!(cs.check_satisfied().is_err());
+    }
+}
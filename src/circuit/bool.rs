@@ -1,3 +1,5 @@
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
 use crate::{
     circuit::num::CNum, core::cs::ConstraintSystem, core::signal::Signal, native::num::Num,
 };
@@ -88,3 +90,213 @@ impl<'a, CS: ConstraintSystem> CBool<'a, CS> {
         Self::from_const(cs, &false)
     }
 }
+
+// all operations cost at most one constraint, the constants are folded
+impl<'a, CS: ConstraintSystem> CBool<'a, CS> {
+    #[inline]
+    pub fn not(&self) -> Self {
+        (self.0.derive_one() - &self.0).into_bool()
+    }
+
+    #[inline]
+    pub fn and(&self, other: &Self) -> Self {
+        (&self.0 * &other.0).into_bool()
+    }
+
+    pub fn or(&self, other: &Self) -> Self {
+        (&self.0 + &other.0 - &self.0 * &other.0).into_bool()
+    }
+
+    pub fn xor(&self, other: &Self) -> Self {
+        let ab = &self.0 * &other.0;
+        (&self.0 + &other.0 - &ab - &ab).into_bool()
+    }
+
+    #[inline]
+    pub fn nand(&self, other: &Self) -> Self {
+        self.and(other).not()
+    }
+
+    pub fn implies(&self, other: &Self) -> Self {
+        (self.0.derive_one() - &self.0 + &self.0 * &other.0).into_bool()
+    }
+}
+
+// costs 2 constraints, x * inv = 1 - res, x * res = 0, one less than CNum::is_zero
+fn is_zero<'a, CS: ConstraintSystem>(x: &CNum<'a, CS>) -> CBool<'a, CS> {
+    match x.as_const() {
+        Some(c) => x.derive_const(&c.is_zero()),
+        _ => {
+            let inv_value = x.get_value().map(|v| {
+                if v.is_zero() {
+                    Num::zero()
+                } else {
+                    v.inverse()
+                }
+            });
+            let inv = x.derive_alloc::<CNum<_>>(inv_value.as_ref());
+            let res = x.derive_one() - &(x * &inv);
+            x.get_cs().enforce(x, &res, &x.derive_zero());
+            res.into_bool()
+        }
+    }
+}
+
+// the product of the bits for a few signals, the sum check for the rest
+pub fn c_all<'a, CS: ConstraintSystem>(bits: &[CBool<'a, CS>]) -> CBool<'a, CS> {
+    assert!(!bits.is_empty(), "should be at least one bit");
+    let cs = bits[0].get_cs();
+    if bits.iter().any(|b| b.as_const() == Some(false)) {
+        return CBool::c_false(cs);
+    }
+    let bits = bits
+        .iter()
+        .filter(|b| b.as_const().is_none())
+        .collect::<Vec<_>>();
+    if bits.len() <= 3 {
+        bits.iter().fold(CBool::c_true(cs), |acc, b| acc.and(b))
+    } else {
+        let _ns = cs.namespace("all");
+        let n = Num::from(bits.len() as u64);
+        is_zero(
+            &bits
+                .iter()
+                .fold(CNum::from_const(cs, &n), |acc, b| acc - &b.0),
+        )
+    }
+}
+
+pub fn c_any<'a, CS: ConstraintSystem>(bits: &[CBool<'a, CS>]) -> CBool<'a, CS> {
+    assert!(!bits.is_empty(), "should be at least one bit");
+    let cs = bits[0].get_cs();
+    if bits.iter().any(|b| b.as_const() == Some(true)) {
+        return CBool::c_true(cs);
+    }
+    let bits = bits
+        .iter()
+        .filter(|b| b.as_const().is_none())
+        .collect::<Vec<_>>();
+    if bits.len() <= 3 {
+        bits.iter().fold(CBool::c_false(cs), |acc, b| acc.or(b))
+    } else {
+        let _ns = cs.namespace("any");
+        is_zero(&bits.iter().fold(CNum::zero(cs), |acc, b| acc + &b.0)).not()
+    }
+}
+
+impl<'a, CS: ConstraintSystem> Not for CBool<'a, CS> {
+    type Output = CBool<'a, CS>;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        CBool::not(&self)
+    }
+}
+
+forward_unop_ex!(impl<'a, CS: ConstraintSystem> Not for CBool<'a, CS>, not);
+
+impl<'l, 'a, CS: ConstraintSystem> BitAnd<&'l CBool<'a, CS>> for CBool<'a, CS> {
+    type Output = CBool<'a, CS>;
+
+    #[inline]
+    fn bitand(self, other: &'l CBool<'a, CS>) -> Self::Output {
+        self.and(other)
+    }
+}
+
+impl<'l, 'a, CS: ConstraintSystem> BitOr<&'l CBool<'a, CS>> for CBool<'a, CS> {
+    type Output = CBool<'a, CS>;
+
+    #[inline]
+    fn bitor(self, other: &'l CBool<'a, CS>) -> Self::Output {
+        self.or(other)
+    }
+}
+
+impl<'l, 'a, CS: ConstraintSystem> BitXor<&'l CBool<'a, CS>> for CBool<'a, CS> {
+    type Output = CBool<'a, CS>;
+
+    #[inline]
+    fn bitxor(self, other: &'l CBool<'a, CS>) -> Self::Output {
+        self.xor(other)
+    }
+}
+
+forward_all_binop_to_val_ref_ex!(impl<'a, CS: ConstraintSystem> BitAnd<CBool<'a, CS>> for CBool<'a, CS>, bitand -> CBool<'a, CS>);
+forward_all_binop_to_val_ref_ex!(impl<'a, CS: ConstraintSystem> BitOr<CBool<'a, CS>> for CBool<'a, CS>, bitor -> CBool<'a, CS>);
+forward_all_binop_to_val_ref_ex!(impl<'a, CS: ConstraintSystem> BitXor<CBool<'a, CS>> for CBool<'a, CS>, bitxor -> CBool<'a, CS>);
+
+#[cfg(test)]
+mod bool_test {
+    use bellman::pairing::bn256::Fr;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::core::cs::TestCS;
+
+    #[test]
+    fn test_bool_ops() {
+        let cs = &TestCS::<Fr>::new();
+        for &a in [false, true].iter() {
+            for &b in [false, true].iter() {
+                let expected = [a && b, a || b, a != b, !(a && b), !a || b];
+                for (const_a, const_b) in [(false, false), (true, false), (false, true)].iter() {
+                    let sa = if *const_a {
+                        CBool::from_const(cs, &a)
+                    } else {
+                        CBool::alloc(cs, Some(&a))
+                    };
+                    let sb = if *const_b {
+                        CBool::from_const(cs, &b)
+                    } else {
+                        CBool::alloc(cs, Some(&b))
+                    };
+                    let n_constraints = cs.num_constraints();
+                    let res = [
+                        &sa & &sb,
+                        &sa | &sb,
+                        &sa ^ &sb,
+                        sa.nand(&sb),
+                        sa.implies(&sb),
+                    ];
+                    let n_expected = if *const_a || *const_b { 0 } else { 5 };
+                    assert!(cs.num_constraints() - n_constraints == n_expected);
+                    for (r, e) in res.iter().zip(expected.iter()) {
+                        assert!(r.get_value() == Some(*e));
+                    }
+                    assert!((!sa.clone()).get_value() == Some(!a));
+                }
+            }
+        }
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_all_any() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        for n in 1..10 {
+            for _ in 0..4 {
+                let values = (0..n)
+                    .map(|_| rng.gen::<u8>() % 5 != 0)
+                    .collect::<Vec<bool>>();
+                let bits = values
+                    .iter()
+                    .map(|v| CBool::alloc(cs, Some(v)))
+                    .collect::<Vec<_>>();
+                let n_constraints = cs.num_constraints();
+                let all = c_all(&bits);
+                assert!(cs.num_constraints() - n_constraints <= 2);
+                assert!(all.get_value() == Some(values.iter().all(|&v| v)));
+                let any = c_any(&bits);
+                assert!(any.get_value() == Some(values.iter().any(|&v| v)));
+            }
+        }
+
+        let x = CBool::alloc(cs, Some(&true));
+        assert!(c_all(&[x.clone(), CBool::c_false(cs)]).as_const() == Some(false));
+        assert!(c_any(&[x.clone(), CBool::c_true(cs)]).as_const() == Some(true));
+        assert!(c_all(&[CBool::c_true(cs), CBool::c_true(cs)]).as_const() == Some(true));
+        assert!(cs.check_satisfied().is_ok());
+    }
+}
@@ -0,0 +1,298 @@
+use std::marker::PhantomData;
+
+use typenum::Unsigned;
+
+use crate::{
+    circuit::bool::CBool, circuit::num::CNum, circuit::poseidon::c_poseidon,
+    core::boundedvec::BoundedVec, core::cs::ConstraintSystem, core::signal::Signal,
+    core::sizedvec::SizedVec, native::num::Num, native::poseidon::PoseidonParams,
+};
+
+// vector of at most L items, mask[i] is set for the first len items, the padding items are set to the
+// default values by alloc and are ignored by comparison and hashing
+#[derive(Clone)]
+pub struct CVec<'a, CS: ConstraintSystem, T: Signal<'a, CS>, L: Unsigned> {
+    pub len: CNum<'a, CS>,
+    pub mask: SizedVec<CBool<'a, CS>, L>,
+    pub items: SizedVec<T, L>,
+}
+
+impl<'a, CS: ConstraintSystem, T: Signal<'a, CS>, L: Unsigned> Signal<'a, CS> for CVec<'a, CS, T, L>
+where
+    T::Value: Default,
+{
+    type Value = BoundedVec<T::Value, L>;
+
+    fn get_cs(&self) -> &'a CS {
+        self.len.get_cs()
+    }
+
+    fn from_const(cs: &'a CS, value: &Self::Value) -> Self {
+        assert!(value.len() <= L::USIZE, "Wrong length of CVec");
+        Self {
+            len: CNum::from_const(cs, &Num::from(value.len() as u64)),
+            mask: (0..L::USIZE)
+                .map(|i| CBool::from_const(cs, &(i < value.len())))
+                .collect(),
+            items: (0..L::USIZE)
+                .map(|i| T::from_const(cs, &value.get(i).cloned().unwrap_or_default()))
+                .collect(),
+        }
+    }
+
+    fn get_value(&self) -> Option<Self::Value> {
+        let mask = self.mask.get_value()?;
+        self.items
+            .iter()
+            .zip(mask.iter())
+            .filter(|(_, &m)| m)
+            .map(|(item, _)| item.get_value())
+            .collect::<Option<Vec<_>>>()
+            .map(|v| BoundedVec(v, PhantomData))
+    }
+
+    fn as_const(&self) -> Option<Self::Value> {
+        let mask = self.mask.as_const()?;
+        self.items
+            .iter()
+            .zip(mask.iter())
+            .filter(|(_, &m)| m)
+            .map(|(item, _)| item.as_const())
+            .collect::<Option<Vec<_>>>()
+            .map(|v| BoundedVec(v, PhantomData))
+    }
+
+    // the mask is checked to be a sequence of ones followed by zeros
+    fn alloc(cs: &'a CS, value: Option<&Self::Value>) -> Self {
+        let _ns = cs.namespace("cvec_alloc");
+        if let Some(value) = value {
+            assert!(value.len() <= L::USIZE, "Wrong length of CVec");
+        }
+        let mask = (0..L::USIZE)
+            .map(|i| {
+                let bit = CBool::alloc(cs, value.map(|v| i < v.len()).as_ref());
+                bit.assert();
+                bit
+            })
+            .collect::<SizedVec<_, L>>();
+        for i in 1..L::USIZE {
+            (&mask[i].0 - &mask[i].0 * &mask[i - 1].0).assert_zero();
+        }
+        let items = (0..L::USIZE)
+            .map(|i| {
+                let item = value.map(|v| v.get(i).cloned().unwrap_or_default());
+                T::alloc(cs, item.as_ref())
+            })
+            .collect();
+        Self {
+            len: mask.iter().fold(CNum::zero(cs), |acc, bit| acc + &bit.0),
+            mask,
+            items,
+        }
+    }
+
+    fn switch(&self, bit: &CBool<'a, CS>, if_else: &Self) -> Self {
+        Self {
+            len: self.len.switch(bit, &if_else.len),
+            mask: self.mask.switch(bit, &if_else.mask),
+            items: self.items.switch(bit, &if_else.items),
+        }
+    }
+
+    fn assert_const(&self, value: &Self::Value) {
+        assert!(value.len() <= L::USIZE, "Wrong length of CVec");
+        self.len.assert_const(&Num::from(value.len() as u64));
+        for (item, v) in self.items.iter().zip(value.iter()) {
+            item.assert_const(v);
+        }
+    }
+
+    fn assert_eq(&self, other: &Self) {
+        self.is_eq(other).assert_true();
+    }
+
+    // the padding items are not compared
+    fn is_eq(&self, other: &Self) -> CBool<'a, CS> {
+        let mut acc = self.len.is_eq(&other.len).0;
+        for ((m, a), b) in self.iter_masked().zip(other.items.iter()) {
+            let ne = m.0.derive_one() - &a.is_eq(b).0;
+            acc *= m.0.derive_one() - &(&m.0 * &ne);
+        }
+        acc.into_bool()
+    }
+
+    fn inputize(&self) {
+        self.len.inputize();
+        self.mask.inputize();
+        self.items.inputize();
+    }
+
+    fn linearize_builder(&self, acc: &mut Vec<CNum<'a, CS>>) {
+        self.len.linearize_builder(acc);
+        self.mask.linearize_builder(acc);
+        self.items.linearize_builder(acc);
+    }
+}
+
+impl<'a, CS: ConstraintSystem, T: Signal<'a, CS>, L: Unsigned> CVec<'a, CS, T, L>
+where
+    T::Value: Default,
+{
+    pub fn empty(cs: &'a CS) -> Self {
+        Self::from_const(cs, &BoundedVec::default())
+    }
+
+    // items are paired with their mask bits, the padding items are paired with false
+    pub fn iter_masked(&self) -> impl Iterator<Item = (&CBool<'a, CS>, &T)> {
+        self.mask.iter().zip(self.items.iter())
+    }
+
+    // appends the item if cond is set, the vector should not be full in this case
+    pub fn push(&mut self, item: &T, cond: &CBool<'a, CS>) {
+        assert!(L::USIZE > 0, "CVec should have nonzero capacity");
+        let _ns = self.get_cs().namespace("cvec_push");
+        (&self.mask[L::USIZE - 1].0 * &cond.0).assert_zero();
+        let mut prev = self.len.derive_one();
+        for i in 0..L::USIZE {
+            // the first free slot is the one with the previous mask bit set
+            let slot = ((&prev - &self.mask[i].0) * &cond.0).into_bool();
+            prev = self.mask[i].0.clone();
+            self.items[i] = item.switch(&slot, &self.items[i]);
+            self.mask[i] = CBool(&self.mask[i].0 + &slot.0);
+        }
+        self.len += &cond.0;
+    }
+
+    // hash of the length and the linearized items, the padding items are replaced with zeros.
+    // It is c_poseidon_chain started from the length instead of the number of inputs, which is
+    // fixed by the capacity and does not tell the vectors apart
+    pub fn hash(&self, params: &PoseidonParams<CS::F>) -> CNum<'a, CS> {
+        assert!(params.t > 1, "t should be greater than 1");
+        let _ns = self.get_cs().namespace("cvec_hash");
+        let mut inputs = vec![];
+        for (m, item) in self.iter_masked() {
+            inputs.extend(item.linearize().iter().map(|x| &m.0 * x));
+        }
+        let mut res = self.len.clone();
+        for i in (0..inputs.len().max(1)).step_by(params.t - 1) {
+            let mut state = vec![res];
+            state.extend_from_slice(&inputs[i..(i + params.t - 1).min(inputs.len())]);
+            res = c_poseidon(&state, params);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod cvec_test {
+    use bellman::pairing::bn256::Fr;
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use fawkes_crypto_derive::Signal;
+    use rand::{thread_rng, Rng};
+    use typenum::{U2, U5};
+
+    use super::*;
+    use crate::{core::cs::TestCS, native::poseidon::poseidon};
+
+    #[derive(Clone, Signal)]
+    #[signal(generate_value = "Tx")]
+    struct CTx<'a, CS: ConstraintSystem> {
+        inputs: CVec<'a, CS, (CNum<'a, CS>, CBool<'a, CS>), U5>,
+        outputs: CVec<'a, CS, CNum<'a, CS>, U2>,
+    }
+
+    #[test]
+    fn test_cvec() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let value = (0..3)
+            .map(|_| rng.gen())
+            .collect::<BoundedVec<Num<Fr>, U5>>();
+
+        let v = CVec::<_, CNum<_>, U5>::alloc(cs, Some(&value));
+        assert!(v.get_value().unwrap().0 == value.0);
+        assert!(v.len.get_value() == Some(Num::from(3)));
+        assert!(v.linearize().len() == 11);
+
+        let mut pushed = CVec::<_, CNum<_>, U5>::empty(cs);
+        for (i, x) in value.iter().enumerate() {
+            let item = CNum::alloc(cs, Some(x));
+            let skipped = CNum::alloc(cs, Some(&rng.gen()));
+            pushed.push(&item, &CBool::alloc(cs, Some(&true)));
+            pushed.push(&skipped, &CBool::alloc(cs, Some(&false)));
+            assert!(pushed.len.get_value() == Some(Num::from(i as u64 + 1)));
+        }
+        assert!(pushed.get_value().unwrap().0 == value.0);
+        assert!(pushed.is_eq(&v).get_value() == Some(true));
+        pushed.assert_eq(&v);
+
+        // the padding items are ignored
+        let mut padded = v.clone();
+        padded.items[4] = CNum::alloc(cs, Some(&rng.gen()));
+        assert!(padded.is_eq(&v).get_value() == Some(true));
+        let shorter =
+            CVec::<_, CNum<_>, U5>::alloc(cs, Some(&value[0..2].iter().cloned().collect()));
+        assert!(shorter.is_eq(&v).get_value() == Some(false));
+
+        let params = PoseidonParams::<Fr>::new(3, 8, 53);
+        let hash = poseidon(&[Num::from(3), value[0], value[1]], &params);
+        let hash = poseidon(&[hash, value[2], Num::zero()], &params);
+        let hash = poseidon(&[hash, Num::zero()], &params);
+        assert!(v.hash(&params).get_value() == Some(hash));
+        assert!(padded.hash(&params).get_value() == Some(hash));
+        // the trailing zero items are told apart by the length
+        let zero =
+            CVec::<_, CNum<_>, U5>::alloc(cs, Some(&vec![Num::zero()].into_iter().collect()));
+        let empty = CVec::<_, CNum<_>, U5>::empty(cs);
+        assert!(zero.hash(&params).get_value() != empty.hash(&params).get_value());
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_cvec_overflow() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new_collecting();
+        let value = (0..5)
+            .map(|_| rng.gen())
+            .collect::<BoundedVec<Num<Fr>, U5>>();
+
+        let mut v = CVec::<_, CNum<_>, U5>::alloc(cs, Some(&value));
+        assert!(cs.check_satisfied().is_ok());
+        v.push(
+            &CNum::alloc(cs, Some(&rng.gen())),
+            &CBool::alloc(cs, Some(&false)),
+        );
+        assert!(cs.check_satisfied().is_ok());
+        v.push(
+            &CNum::alloc(cs, Some(&rng.gen())),
+            &CBool::alloc(cs, Some(&true)),
+        );
+        assert!(cs.check_satisfied().is_err());
+    }
+
+    #[test]
+    fn test_cvec_derive() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let tx = Tx::<Fr> {
+            inputs: (0..2).map(|_| (rng.gen(), rng.gen())).collect(),
+            outputs: (0..1).map(|_| rng.gen()).collect(),
+        };
+
+        let signal = CTx::alloc(cs, Some(&tx));
+        let value = signal.get_value().unwrap();
+        assert!(value.inputs.0 == tx.inputs.0 && value.outputs.0 == tx.outputs.0);
+        assert!(signal.inputs.len.get_value() == Some(Num::from(2)));
+        assert!(signal.is_eq(&signal.clone()).get_value() == Some(true));
+        assert!(cs.check_satisfied().is_ok());
+
+        let bin = tx.try_to_vec().unwrap();
+        assert!(Tx::<Fr>::try_from_slice(&bin).unwrap().inputs.0 == tx.inputs.0);
+
+        // the values longer than the capacity are rejected
+        let json = serde_json::to_string(&tx).unwrap();
+        assert!(serde_json::from_str::<Tx<Fr>>(&json).is_ok());
+        let long = json.replacen("\"outputs\":[", "\"outputs\":[\"1\",\"2\",", 1);
+        assert!(serde_json::from_str::<Tx<Fr>>(&long).is_err());
+    }
+}
@@ -60,6 +60,7 @@ impl<'a, CS: ConstraintSystem> CEdwardsPoint<'a, CS> {
     }
 
     pub fn assert_in_subgroup<J: JubJubParams<Fr = CS::F>>(&self, params: &J) {
+        let _ns = self.get_cs().namespace("assert_in_subgroup");
         let preimage_value = self.get_value().map(|p| p.mul(num!(8).inverse(), params));
         let preimage = self.derive_alloc::<Self>(preimage_value.as_ref());
         preimage.assert_in_curve(params);
@@ -70,6 +71,7 @@ impl<'a, CS: ConstraintSystem> CEdwardsPoint<'a, CS> {
     }
 
     pub fn subgroup_decompress<J: JubJubParams<Fr = CS::F>>(x: &CNum<'a, CS>, params: &J) -> Self {
+        let _ns = x.get_cs().namespace("subgroup_decompress");
         let preimage_value = x.get_value().map(|x| {
             EdwardsPoint::subgroup_decompress(x, params)
                 .unwrap_or(params.edwards_g().clone())
@@ -110,6 +112,7 @@ impl<'a, CS: ConstraintSystem> CEdwardsPoint<'a, CS> {
 
         match self.as_const() {
             Some(c_base) => {
+                let _ns = cs.namespace("edwards_mul_const");
                 let c_base = c_base.into_extended();
                 let mut base = c_base;
                 if base.is_zero() {
@@ -158,6 +161,7 @@ impl<'a, CS: ConstraintSystem> CEdwardsPoint<'a, CS> {
                 }
             }
             _ => {
+                let _ns = cs.namespace("edwards_mul");
                 let base_is_zero = self.x.is_zero();
                 let dummy_point = CEdwardsPoint::from_const(cs, params.edwards_g());
                 let base_point = dummy_point.switch(&base_is_zero, self);
@@ -195,6 +199,7 @@ impl<'a, CS: ConstraintSystem> CEdwardsPoint<'a, CS> {
 
     // assuming t!=-1
     pub fn from_scalar<J: JubJubParams<Fr = CS::F>>(t: &CNum<'a, CS>, params: &J) -> Self {
+        let _ns = t.get_cs().namespace("edwards_from_scalar");
         fn filter_even<F: Field>(x: Num<F>) -> Num<F> {
             if x.is_even() {
                 x
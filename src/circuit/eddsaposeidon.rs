@@ -25,6 +25,7 @@ pub fn c_eddsaposeidon_verify<'a, CS: ConstraintSystem, J: JubJubParams<Fr = CS:
         "jubjub field should be lesser than snark field"
     );
     let cs = s.cs;
+    let _ns = cs.namespace("eddsaposeidon_verify");
 
     let p_a = CEdwardsPoint::subgroup_decompress(a, jubjub_params);
     let p_r = CEdwardsPoint::subgroup_decompress(r, jubjub_params);
@@ -0,0 +1,267 @@
+use crate::{
+    circuit::bitify::{c_from_bits_le, c_into_bits_le_strict},
+    circuit::bool::CBool,
+    circuit::num::CNum,
+    circuit::uint::{CUint64, CUint8},
+    core::cs::ConstraintSystem,
+    core::signal::Signal,
+    native::keccak::{keccak256_shift, keccak_pi, KECCAK256_RATE, KECCAK_RC, KECCAK_ROTATIONS},
+};
+
+// the result is a fresh variable, 2a * b = a + b - res, so the linear combinations do not grow
+// over the rounds, costs one constraint per bit
+fn xor<'a, CS: ConstraintSystem>(a: &CUint64<'a, CS>, b: &CUint64<'a, CS>) -> CUint64<'a, CS> {
+    CUint64::from_bits(
+        a.bits
+            .iter()
+            .zip(b.bits.iter())
+            .map(|(a, b)| {
+                if a.as_const().is_some() || b.as_const().is_some() {
+                    return a.xor(b);
+                }
+                let res = a.derive_alloc::<CBool<_>>(
+                    a.get_value()
+                        .and_then(|a| Some(a ^ b.get_value()?))
+                        .as_ref(),
+                );
+                a.get_cs()
+                    .enforce(&(&a.0 + &a.0), &b.0, &(&a.0 + &b.0 - &res.0));
+                res
+            })
+            .collect(),
+    )
+}
+
+// a ^ (!b & c), costs two constraints per bit
+fn chi<'a, CS: ConstraintSystem>(
+    a: &CUint64<'a, CS>,
+    b: &CUint64<'a, CS>,
+    c: &CUint64<'a, CS>,
+) -> CUint64<'a, CS> {
+    let t = CUint64::from_bits(
+        b.bits
+            .iter()
+            .zip(c.bits.iter())
+            .map(|(b, c)| (&c.0 - &b.0 * &c.0).into_bool())
+            .collect(),
+    );
+    xor(a, &t)
+}
+
+// the lane (x, y) has index x + 5*y
+pub fn c_keccak_f<'a, CS: ConstraintSystem>(state: &[CUint64<'a, CS>]) -> Vec<CUint64<'a, CS>> {
+    assert!(state.len() == 25, "Wrong length of state");
+    let cs = state[0].get_cs();
+    let _ns = cs.namespace("keccak_f");
+    let mut a = state.to_vec();
+    for rc in KECCAK_RC.iter() {
+        let c = (0..5)
+            .map(|x| (1..5).fold(a[x].clone(), |acc, y| xor(&acc, &a[x + 5 * y])))
+            .collect::<Vec<_>>();
+        for x in 0..5 {
+            let d = xor(&c[(x + 4) % 5], &c[(x + 1) % 5].rotate_left(1));
+            for y in 0..5 {
+                a[x + 5 * y] = xor(&a[x + 5 * y], &d);
+            }
+        }
+
+        let mut b = a.clone();
+        for x in 0..5 {
+            for y in 0..5 {
+                b[keccak_pi(x, y)] = a[x + 5 * y].rotate_left(KECCAK_ROTATIONS[x + 5 * y] as usize);
+            }
+        }
+
+        for x in 0..5 {
+            for y in 0..5 {
+                a[x + 5 * y] = chi(
+                    &b[x + 5 * y],
+                    &b[(x + 1) % 5 + 5 * y],
+                    &b[(x + 2) % 5 + 5 * y],
+                );
+            }
+        }
+        a[0] = a[0].xor(&CUint64::from_const(cs, rc));
+    }
+    a
+}
+
+// hash of the message bytes, matches keccak256 in Solidity
+pub fn c_keccak256<'a, CS: ConstraintSystem>(
+    cs: &'a CS,
+    data: &[CUint8<'a, CS>],
+) -> Vec<CUint8<'a, CS>> {
+    let _ns = cs.namespace("keccak256");
+    let mut bytes = data.to_vec();
+    let padding = KECCAK256_RATE - data.len() % KECCAK256_RATE;
+    bytes.extend((0..padding).map(|i| {
+        let first = if i == 0 { 0x01 } else { 0 };
+        let last = if i == padding - 1 { 0x80 } else { 0 };
+        CUint8::from_const(cs, &(first | last))
+    }));
+
+    let mut state = vec![CUint64::from_const(cs, &0); 25];
+    for block in bytes.chunks(KECCAK256_RATE) {
+        for (s, lane) in state.iter_mut().zip(block.chunks(8)) {
+            let bits = lane
+                .iter()
+                .flat_map(|b| b.bits.iter().cloned())
+                .collect::<Vec<CBool<'a, CS>>>();
+            *s = xor(s, &CUint64::from_bits(bits));
+        }
+        state = c_keccak_f(&state);
+    }
+    state[0..4]
+        .iter()
+        .flat_map(|s| {
+            s.bits
+                .chunks(8)
+                .map(|chunk| CUint8::from_bits(chunk.to_vec()))
+        })
+        .collect()
+}
+
+// the signal packed as uint256 by abi.encodePacked in Solidity, the strict decomposition keeps
+// the bytes unique
+pub fn c_num_to_bytes_be<'a, CS: ConstraintSystem>(signal: &CNum<'a, CS>) -> Vec<CUint8<'a, CS>> {
+    let cs = signal.get_cs();
+    let _ns = cs.namespace("num_to_bytes_be");
+    let mut bits = c_into_bits_le_strict(signal);
+    bits.resize(256, CBool::c_false(cs));
+    bits.chunks(8)
+        .rev()
+        .map(|chunk| CUint8::from_bits(chunk.to_vec()))
+        .collect()
+}
+
+// uint256(h) >> shift in Solidity, the lowest bits are dropped, so the digest fits into the field
+pub fn c_keccak256_to_num<'a, CS: ConstraintSystem>(digest: &[CUint8<'a, CS>]) -> CNum<'a, CS> {
+    assert!(digest.len() == 32, "Wrong length of digest");
+    let bits = digest
+        .iter()
+        .rev()
+        .flat_map(|b| b.bits.iter().cloned())
+        .skip(keccak256_shift::<CS::F>())
+        .collect::<Vec<_>>();
+    c_from_bits_le(&bits)
+}
+
+// keccak256(abi.encodePacked(inputs)) reduced into the field, inputize it instead of the inputs
+// and compute it on chain with generate_sol_input_hash
+pub fn c_keccak256_inputs<'a, CS: ConstraintSystem>(
+    cs: &'a CS,
+    inputs: &[CNum<'a, CS>],
+) -> CNum<'a, CS> {
+    let _ns = cs.namespace("keccak256_inputs");
+    let data = inputs
+        .iter()
+        .flat_map(|n| c_num_to_bytes_be(n))
+        .collect::<Vec<_>>();
+    c_keccak256_to_num(&c_keccak256(cs, &data))
+}
+
+#[cfg(test)]
+mod keccak_test {
+    use bellman::pairing::bn256::Fr;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::{
+        core::cs::TestCS,
+        native::keccak::{keccak256, keccak256_inputs, keccak_f, num_to_bytes_be},
+        native::num::Num,
+    };
+
+    #[test]
+    fn test_keccak_f() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let mut state = [0u64; 25];
+        state.iter_mut().for_each(|s| *s = rng.gen());
+        let signal = state
+            .iter()
+            .map(|s| CUint64::alloc(cs, Some(s)))
+            .collect::<Vec<_>>();
+
+        let res = c_keccak_f(&signal);
+        keccak_f(&mut state);
+        assert!(res
+            .iter()
+            .map(|s| s.get_value().unwrap())
+            .eq(state.iter().cloned()));
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_keccak256() {
+        let cs = &TestCS::<Fr>::new();
+        let signal = b"abc"
+            .iter()
+            .map(|b| CUint8::alloc(cs, Some(b)))
+            .collect::<Vec<_>>();
+        let res = c_keccak256(cs, &signal);
+        assert!(res
+            .iter()
+            .map(|b| b.get_value().unwrap())
+            .eq(keccak256(b"abc").iter().cloned()));
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_keccak256_two_blocks() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let data = (0..200).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let signal = data
+            .iter()
+            .map(|b| CUint8::alloc(cs, Some(b)))
+            .collect::<Vec<_>>();
+        let res = c_keccak256(cs, &signal);
+        assert!(res
+            .iter()
+            .map(|b| b.get_value().unwrap())
+            .eq(keccak256(&data).iter().cloned()));
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_keccak256_inputs() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let inputs = [rng.gen(), rng.gen(), -Num::one()];
+        let signal = inputs
+            .iter()
+            .map(|n| CNum::alloc(cs, Some(n)))
+            .collect::<Vec<_>>();
+
+        let bytes = c_num_to_bytes_be(&signal[2]);
+        assert!(bytes
+            .iter()
+            .map(|b| b.get_value().unwrap())
+            .eq(num_to_bytes_be(inputs[2]).iter().cloned()));
+
+        let res = c_keccak256_inputs(cs, &signal);
+        assert!(res.get_value() == Some(keccak256_inputs(&inputs)));
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    // the constants are folded, so the padding of multiple blocks is checked without constraints
+    #[test]
+    fn test_keccak256_const() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        for &len in [0, 3, 135, 136, 200].iter() {
+            let data = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let signal = data
+                .iter()
+                .map(|b| CUint8::from_const(cs, b))
+                .collect::<Vec<_>>();
+            let res = c_keccak256(cs, &signal);
+            assert!(res
+                .iter()
+                .map(|b| b.as_const().unwrap())
+                .eq(keccak256(&data).iter().cloned()));
+        }
+        assert!(cs.num_constraints() == 0);
+    }
+}
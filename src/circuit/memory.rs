@@ -0,0 +1,188 @@
+use fawkes_crypto_derive::Signal;
+use num::bigint::BigUint;
+
+use crate::{
+    circuit::bitify::{c_comp, c_into_bits_le},
+    circuit::bool::CBool,
+    circuit::num::CNum,
+    circuit::poseidon::{c_poseidon, c_poseidon_chain},
+    core::cs::ConstraintSystem,
+    core::signal::Signal,
+    native::num::Num,
+    native::poseidon::PoseidonParams,
+};
+
+#[derive(Clone, Signal)]
+#[signal(generate_value = "MemoryAccess")]
+pub struct CMemoryAccess<'a, CS: ConstraintSystem> {
+    pub addr: CNum<'a, CS>,
+    pub time: CNum<'a, CS>,
+    pub value: CNum<'a, CS>,
+    pub is_write: CBool<'a, CS>,
+}
+
+// RAM with zero initialized cells. The accesses are recorded in the program order, finalize checks
+// that they are a permutation of the accesses sorted by address and time, and that every read of
+// the sorted accesses returns the last written value.
+#[must_use = "the accesses are not constrained until finalize is called"]
+pub struct CMemory<'a, CS: ConstraintSystem> {
+    pub cs: &'a CS,
+    pub addr_bits: usize,
+    trace: Vec<CMemoryAccess<'a, CS>>,
+}
+
+impl<'a, CS: ConstraintSystem> CMemory<'a, CS> {
+    pub fn new(cs: &'a CS, addr_bits: usize) -> Self {
+        Self {
+            cs,
+            addr_bits,
+            trace: vec![],
+        }
+    }
+
+    fn last_value(&self, addr: Num<CS::F>) -> Option<Num<CS::F>> {
+        for a in self.trace.iter().rev() {
+            if a.is_write.as_const() == Some(true) && a.addr.get_value()? == addr {
+                return a.value.get_value();
+            }
+        }
+        Some(Num::zero())
+    }
+
+    fn access(&mut self, addr: &CNum<'a, CS>, value: &CNum<'a, CS>, is_write: bool) {
+        let time = Num::from(self.trace.len() as u64);
+        self.trace.push(CMemoryAccess {
+            addr: addr.clone(),
+            time: CNum::from_const(self.cs, &time),
+            value: value.clone(),
+            is_write: CBool::from_const(self.cs, &is_write),
+        });
+    }
+
+    // the value is not constrained until finalize
+    pub fn read(&mut self, addr: &CNum<'a, CS>) -> CNum<'a, CS> {
+        let value = addr.get_value().and_then(|addr| self.last_value(addr));
+        let value = CNum::alloc(self.cs, value.as_ref());
+        self.access(addr, &value, false);
+        value
+    }
+
+    pub fn write(&mut self, addr: &CNum<'a, CS>, value: &CNum<'a, CS>) {
+        self.access(addr, value, true);
+    }
+
+    pub fn finalize(self, params: &PoseidonParams<CS::F>) {
+        let n = self.trace.len();
+        if n == 0 {
+            return;
+        }
+        let cs = self.cs;
+        let _ns = cs.namespace("memory");
+        let time_bits = std::mem::size_of::<usize>() * 8 - n.leading_zeros() as usize;
+
+        let sorted_values = self
+            .trace
+            .iter()
+            .map(|a| a.get_value())
+            .collect::<Option<Vec<_>>>()
+            .map(|mut v| {
+                v.sort_by_key(|a| {
+                    let (addr, time): (BigUint, BigUint) = (a.addr.into(), a.time.into());
+                    (addr, time)
+                });
+                v
+            });
+        // the bits check the ranges and are kept for the comparison of the neighbours
+        let (sorted, bits): (Vec<_>, Vec<_>) = (0..n)
+            .map(|i| {
+                let a = CMemoryAccess::alloc(cs, sorted_values.as_ref().map(|v| &v[i]));
+                a.is_write.assert();
+                let addr_bits = c_into_bits_le(&a.addr, self.addr_bits);
+                let time_bits = c_into_bits_le(&a.time, time_bits);
+                (a, (addr_bits, time_bits))
+            })
+            .unzip();
+
+        // the challenges depend on both sequences, the constants of the trace are fixed by the
+        // circuit and are not hashed
+        let mut inputs = vec![];
+        for a in self.trace.iter().chain(sorted.iter()) {
+            a.linearize_builder(&mut inputs);
+        }
+        inputs.retain(|x| x.as_const().is_none());
+        let alpha = c_poseidon_chain(cs, &inputs, params);
+        let gamma = c_poseidon(std::slice::from_ref(&alpha), params);
+        let alpha2 = alpha.square();
+        let alpha3 = &alpha2 * &alpha;
+        let product = |accesses: &[CMemoryAccess<'a, CS>]| {
+            accesses.iter().fold(CNum::one(cs), |acc, a| {
+                let fingerprint =
+                    &a.addr + &alpha * &a.time + &alpha2 * &a.value + &alpha3 * &a.is_write.0;
+                acc * (&gamma - &fingerprint)
+            })
+        };
+        product(&self.trace).assert_eq(&product(&sorted));
+
+        (&sorted[0].is_write.not().0 * &sorted[0].value).assert_zero();
+        for i in 1..n {
+            let (prev, cur) = (&sorted[i - 1], &sorted[i]);
+            let ((prev_addr, prev_time), (cur_addr, cur_time)) = (&bits[i - 1], &bits[i]);
+            let same = cur.addr.is_eq(&prev.addr);
+            same.or(&c_comp(cur_addr, prev_addr)).assert_true();
+            same.implies(&c_comp(cur_time, prev_time)).assert_true();
+            // the first access of the address reads zero
+            let expected = same.if_else(&prev.value, &cur.value.derive_zero());
+            (&cur.is_write.not().0 * &(&cur.value - &expected)).assert_zero();
+        }
+    }
+}
+
+#[cfg(test)]
+mod memory_test {
+    use bellman::pairing::bn256::Fr;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::core::cs::TestCS;
+
+    #[test]
+    fn test_memory() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let params = PoseidonParams::<Fr>::new(3, 8, 53);
+        let mut memory = CMemory::new(cs, 8);
+        let mut model = [Num::zero(); 6];
+
+        for _ in 0..30 {
+            let addr = rng.gen::<usize>() % model.len();
+            let signal_addr = CNum::alloc(cs, Some(&Num::from(addr as u64)));
+            if rng.gen() {
+                let value = rng.gen();
+                memory.write(&signal_addr, &CNum::alloc(cs, Some(&value)));
+                model[addr] = value;
+            } else {
+                assert!(memory.read(&signal_addr).get_value() == Some(model[addr]));
+            }
+        }
+        memory.finalize(&params);
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_memory_wrong_read() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new_collecting();
+        let params = PoseidonParams::<Fr>::new(3, 8, 53);
+        let mut memory = CMemory::new(cs, 8);
+
+        let addr = CNum::alloc(cs, Some(&num!(3)));
+        let value = CNum::alloc(cs, Some(&rng.gen()));
+        memory.write(&addr, &value);
+        assert!(memory.read(&addr).get_value() == value.get_value());
+        let other = CNum::alloc(cs, Some(&num!(4)));
+        assert!(memory.read(&other).get_value() == Some(Num::zero()));
+        memory.access(&addr, &CNum::alloc(cs, Some(&rng.gen())), false);
+        memory.finalize(&params);
+        assert!(cs.check_satisfied().is_err());
+    }
+}
@@ -1,7 +1,13 @@
+pub mod array;
 pub mod bitify;
 pub mod bool;
+pub mod cvec;
 pub mod ecc;
 pub mod eddsaposeidon;
+pub mod keccak;
+pub mod memory;
 pub mod mux;
 pub mod num;
 pub mod poseidon;
+pub mod sha256;
+pub mod uint;
@@ -1,5 +1,10 @@
 use crate::{
-    circuit::bool::CBool, circuit::num::CNum, core::cs::ConstraintSystem, native::num::Num,
+    circuit::bitify::{c_comp_constant, c_into_bits_le},
+    circuit::bool::CBool,
+    circuit::num::CNum,
+    core::cs::ConstraintSystem,
+    core::signal::Signal,
+    native::num::Num,
 };
 
 // this method is described here https://iden3.readthedocs.io/en/latest/iden3_repos/research/publications/zkproof-standards-workshop-2/pedersen-hash/pedersen.html
@@ -32,3 +37,208 @@ pub fn c_mux3<'a, CS: ConstraintSystem>(
     }
     res
 }
+
+// c[i][j] is the j-th constant of the i-th output, s[0] is the lowest bit of the index. The table
+// is expanded into a multilinear polynomial, the monomials of the lower bits cost
+// 2^(n-1) - n constraints, every output costs one more constraint for the highest bit.
+pub fn c_mux_n<'a, CS: ConstraintSystem>(
+    s: &[CBool<'a, CS>],
+    c: &[Vec<Num<CS::F>>],
+) -> Vec<CNum<'a, CS>> {
+    let n = s.len();
+    assert!(n > 0, "should be at least one bit");
+    assert!(
+        c.iter().all(|c| c.len() == 1 << n),
+        "should be 2^n constants"
+    );
+    let cs = s[0].get_cs();
+    let _ns = cs.namespace("mux_n");
+
+    let half = 1 << (n - 1);
+    let mut monomials = vec![CNum::one(cs)];
+    for bit in s[0..n - 1].iter() {
+        for j in 0..monomials.len() {
+            let m = &monomials[j] * &bit.0;
+            monomials.push(m);
+        }
+    }
+
+    c.iter()
+        .map(|c| {
+            // inclusion-exclusion over the table gives the coefficients of the monomials
+            let mut a = c.clone();
+            for i in 0..n {
+                for j in 0..1 << n {
+                    if j & (1 << i) != 0 {
+                        a[j] = a[j] - a[j ^ (1 << i)];
+                    }
+                }
+            }
+            let lc = |a: &[Num<CS::F>]| {
+                a.iter()
+                    .zip(monomials.iter())
+                    .fold(CNum::zero(cs), |acc, (k, m)| acc + m * *k)
+            };
+            lc(&a[0..half]) + lc(&a[half..]) * &s[n - 1].0
+        })
+        .collect()
+}
+
+pub fn c_mux2<'a, CS: ConstraintSystem>(
+    s: &[CBool<'a, CS>],
+    c: &[Vec<Num<CS::F>>],
+) -> Vec<CNum<'a, CS>> {
+    assert!(s.len() == 2, "should be 2 bits");
+    c_mux_n(s, c)
+}
+
+pub fn c_mux4<'a, CS: ConstraintSystem>(
+    s: &[CBool<'a, CS>],
+    c: &[Vec<Num<CS::F>>],
+) -> Vec<CNum<'a, CS>> {
+    assert!(s.len() == 4, "should be 4 bits");
+    c_mux_n(s, c)
+}
+
+// splits the bits into the windows of the given width, the last window is padded with zeros,
+// tables[i] is the lookup table of the i-th window
+pub fn c_mux_windows<'a, CS: ConstraintSystem>(
+    s: &[CBool<'a, CS>],
+    window: usize,
+    tables: &[Vec<Vec<Num<CS::F>>>],
+) -> Vec<Vec<CNum<'a, CS>>> {
+    assert!(!s.is_empty(), "should be at least one bit");
+    assert!(window > 0, "window should be nonzero");
+    let nwindows = (s.len() - 1) / window + 1;
+    assert!(
+        tables.len() == nwindows,
+        "should be a table for every window"
+    );
+    let cs = s[0].get_cs();
+    s.chunks(window)
+        .zip(tables.iter())
+        .map(|(bits, table)| {
+            let mut bits = bits.to_vec();
+            bits.resize(window, CBool::c_false(cs));
+            c_mux_n(&bits, table)
+        })
+        .collect()
+}
+
+// selects items[index], the index is checked to be less than the number of items
+pub fn c_select<'a, CS: ConstraintSystem, T: Signal<'a, CS>>(
+    index: &CNum<'a, CS>,
+    items: &[T],
+) -> T {
+    assert!(!items.is_empty(), "should be at least one item");
+    let _ns = index.get_cs().namespace("select");
+    let nbits = std::mem::size_of::<usize>() * 8 - (items.len() - 1).leading_zeros() as usize;
+    if nbits == 0 {
+        index.assert_zero();
+        return items[0].clone();
+    }
+    let bits = c_into_bits_le(index, nbits);
+    if !items.len().is_power_of_two() {
+        c_comp_constant(&bits, Num::from(items.len() as u64 - 1)).assert_false();
+    }
+
+    let mut level = items.to_vec();
+    for bit in bits.iter() {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => bit.if_else(b, a),
+                _ => pair[0].clone(),
+            })
+            .collect();
+    }
+    level[0].clone()
+}
+
+#[cfg(test)]
+mod mux_test {
+    use bellman::pairing::bn256::Fr;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::core::cs::TestCS;
+
+    fn index_bits<'a>(cs: &'a TestCS<Fr>, index: usize, n: usize) -> Vec<CBool<'a, TestCS<Fr>>> {
+        (0..n)
+            .map(|i| CBool::alloc(cs, Some(&(index >> i & 1 == 1))))
+            .collect()
+    }
+
+    #[test]
+    fn test_mux_n() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        for n in 1..6 {
+            let c = (0..3)
+                .map(|_| (0..1 << n).map(|_| rng.gen()).collect())
+                .collect::<Vec<Vec<Num<Fr>>>>();
+            for index in 0..1 << n {
+                let s = index_bits(cs, index, n);
+                let n_constraints = cs.num_constraints();
+                let res = c_mux_n(&s, &c);
+                let expected = if n == 1 { 0 } else { (1 << (n - 1)) - n + 3 };
+                assert!(cs.num_constraints() - n_constraints == expected);
+                for (r, c) in res.iter().zip(c.iter()) {
+                    assert!(r.get_value() == Some(c[index]));
+                }
+            }
+        }
+
+        let c = vec![(0..8).map(|_| rng.gen()).collect::<Vec<Num<Fr>>>()];
+        let s = index_bits(cs, 5, 3);
+        assert!(c_mux_n(&s, &c)[0].get_value() == c_mux3(&s, &c)[0].get_value());
+        let c = vec![(0..16).map(|_| rng.gen()).collect::<Vec<Num<Fr>>>()];
+        assert!(c_mux4(&index_bits(cs, 11, 4), &c)[0].get_value() == Some(c[0][11]));
+        let c = vec![(0..4).map(|_| rng.gen()).collect::<Vec<Num<Fr>>>()];
+        assert!(c_mux2(&index_bits(cs, 2, 2), &c)[0].get_value() == Some(c[0][2]));
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_mux_windows() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let value = 0b1_0110_1101u64;
+        let s = index_bits(cs, value as usize, 9);
+        let tables = (0..5)
+            .map(|_| vec![(0..4).map(|_| rng.gen()).collect::<Vec<Num<Fr>>>()])
+            .collect::<Vec<_>>();
+        let res = c_mux_windows(&s, 2, &tables);
+        for i in 0..5 {
+            let index = (value >> (2 * i) & 3) as usize;
+            assert!(res[i][0].get_value() == Some(tables[i][0][index]));
+        }
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_select() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new_collecting();
+        let values = (0..5)
+            .map(|_| (rng.gen(), rng.gen()))
+            .collect::<Vec<(Num<Fr>, bool)>>();
+        let items = values
+            .iter()
+            .map(|v| <(CNum<_>, CBool<_>)>::alloc(cs, Some(v)))
+            .collect::<Vec<_>>();
+        for (i, v) in values.iter().enumerate() {
+            let index = CNum::alloc(cs, Some(&Num::from(i as u64)));
+            assert!(c_select(&index, &items).get_value() == Some(*v));
+            let index = CNum::from_const(cs, &Num::from(i as u64));
+            assert!(c_select(&index, &items).get_value() == Some(*v));
+        }
+        assert!(
+            c_select(&CNum::alloc(cs, Some(&num!(0))), &items[0..1]).get_value() == Some(values[0])
+        );
+        assert!(cs.check_satisfied().is_ok());
+
+        c_select(&CNum::alloc(cs, Some(&num!(5))), &items);
+        assert!(cs.check_satisfied().is_err());
+    }
+}
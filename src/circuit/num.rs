@@ -1,13 +1,16 @@
 use std::cmp::Ordering;
+use std::io::{self, ErrorKind, Write};
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use linked_list::{Cursor, LinkedList};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    circuit::bool::CBool, core::cs::ConstraintSystem, core::signal::Signal, native::num::Num,
+    circuit::bool::CBool, core::cs::ConstraintSystem, core::field::Field, core::signal::Signal,
+    native::num::Num,
 };
 
-#[derive(Eq, PartialEq, Clone, Copy, Debug, Hash)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Hash, Serialize, Deserialize)]
 pub enum Index {
     Input(usize),
     Aux(usize),
@@ -30,10 +33,34 @@ impl Ord for Index {
     }
 }
 
+impl BorshSerialize for Index {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        let (kind, i) = match self {
+            Index::Input(i) => (0u8, *i),
+            Index::Aux(i) => (1u8, *i),
+        };
+        BorshSerialize::serialize(&kind, writer)?;
+        BorshSerialize::serialize(&(i as u64), writer)
+    }
+}
+
+impl BorshDeserialize for Index {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, io::Error> {
+        let kind: u8 = BorshDeserialize::deserialize(buf)?;
+        let i: u64 = BorshDeserialize::deserialize(buf)?;
+        let i = i as usize;
+        match kind {
+            0 => Ok(Index::Input(i)),
+            1 => Ok(Index::Aux(i)),
+            _ => Err(io::Error::new(ErrorKind::InvalidData, "Wrong index kind")),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CNum<'a, CS: ConstraintSystem> {
     pub value: Option<Num<CS::F>>,
-    pub lc: LinkedList<(Index, Num<CS::F>)>,
+    pub lc: Vec<(Index, Num<CS::F>)>,
     pub cs: &'a CS,
 }
 
@@ -95,10 +122,10 @@ impl<'a, CS: ConstraintSystem> Signal<'a, CS> for CNum<'a, CS> {
     }
 
     fn as_const(&self) -> Option<Self::Value> {
-        if self.lc.len() == 0 {
+        if self.lc.is_empty() {
             Some(Num::zero())
         } else if self.lc.len() == 1 {
-            let front = self.lc.front().unwrap();
+            let front = &self.lc[0];
             if front.0 == Index::Input(0) {
                 Some(front.1)
             } else {
@@ -111,8 +138,7 @@ impl<'a, CS: ConstraintSystem> Signal<'a, CS> for CNum<'a, CS> {
 
     #[inline]
     fn from_const(cs: &'a CS, value: &Self::Value) -> Self {
-        let mut lc = LinkedList::new();
-        lc.push_back((Index::Input(0), value.clone()));
+        let lc = vec![(Index::Input(0), *value)];
         let value = Some(value.clone());
         Self { value, lc, cs }
     }
@@ -152,8 +178,7 @@ impl<'a, CS: ConstraintSystem> CNum<'a, CS> {
 
     #[inline]
     pub fn from_var(cs: &'a CS, value: Option<Num<CS::F>>, var: Index) -> Self {
-        let mut lc = LinkedList::new();
-        lc.push_back((var, Num::one()));
+        let lc = vec![(var, Num::one())];
         Self { value, lc, cs }
     }
 
@@ -187,6 +212,21 @@ impl<'a, CS: ConstraintSystem> CNum<'a, CS> {
         self * self
     }
 
+    // replaces the linear combination with a fresh variable, costs one constraint
+    pub fn compact(&self) -> Self {
+        let res = self.derive_alloc::<Self>(self.get_value().as_ref());
+        self.cs.enforce(self, &self.derive_one(), &res);
+        res
+    }
+
+    fn compact_if_exceeds_limit(&mut self) {
+        if let Some(limit) = self.cs.lc_limit() {
+            if self.capacity() > limit {
+                *self = self.compact();
+            }
+        }
+    }
+
     pub fn assert_zero(&self) {
         self.assert_const(&Num::zero());
     }
@@ -269,29 +309,38 @@ impl<'a, CS: ConstraintSystem> Neg for CNum<'a, CS> {
 
 forward_unop_ex!(impl<'a, CS: ConstraintSystem> Neg for CNum<'a, CS>, neg);
 
-#[derive(Eq, PartialEq)]
-enum LookupAction {
-    Add,
-    Insert,
-}
-
+// merges two linear combinations sorted by Index, dropping the cancelled terms
 #[inline]
-fn ll_lookup<K: PartialEq + PartialOrd, V>(cur: &mut Cursor<(K, V)>, n: K) -> LookupAction {
-    loop {
-        match cur.peek_next() {
-            Some((k, _)) => {
-                if *k == n {
-                    return LookupAction::Add;
-                } else if *k > n {
-                    return LookupAction::Insert;
-                }
+pub(crate) fn lc_merge<F: Field>(
+    a: &[(Index, Num<F>)],
+    b: &[(Index, Num<F>)],
+    f: impl Fn(Num<F>) -> Num<F>,
+) -> Vec<(Index, Num<F>)> {
+    let mut res = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].0.cmp(&b[j].0) {
+            Ordering::Less => {
+                res.push(a[i]);
+                i += 1;
             }
-            None => {
-                return LookupAction::Insert;
+            Ordering::Greater => {
+                res.push((b[j].0, f(b[j].1)));
+                j += 1;
+            }
+            Ordering::Equal => {
+                let v = a[i].1 + f(b[j].1);
+                if !v.is_zero() {
+                    res.push((a[i].0, v));
+                }
+                i += 1;
+                j += 1;
             }
         }
-        cur.seek_forward(1);
     }
+    res.extend_from_slice(&a[i..]);
+    res.extend(b[j..].iter().map(|&(k, v)| (k, f(v))));
+    res
 }
 
 impl<'l, 'a, CS: ConstraintSystem> AddAssign<&'l CNum<'a, CS>> for CNum<'a, CS> {
@@ -302,19 +351,8 @@ impl<'l, 'a, CS: ConstraintSystem> AddAssign<&'l CNum<'a, CS>> for CNum<'a, CS>
             _ => None,
         };
 
-        let mut cur_a_ll = self.lc.cursor();
-
-        for (k, v) in other.lc.iter() {
-            if ll_lookup(&mut cur_a_ll, *k) == LookupAction::Add {
-                let t = cur_a_ll.peek_next().unwrap();
-                t.1 += *v;
-                if t.1.is_zero() {
-                    cur_a_ll.remove();
-                }
-            } else {
-                cur_a_ll.insert((*k, *v))
-            }
-        }
+        self.lc = lc_merge(&self.lc, &other.lc, |v| v);
+        self.compact_if_exceeds_limit();
     }
 }
 
@@ -333,19 +371,8 @@ impl<'l, 'a, CS: ConstraintSystem> SubAssign<&'l CNum<'a, CS>> for CNum<'a, CS>
             _ => None,
         };
 
-        let mut cur_a_ll = self.lc.cursor();
-
-        for (k, v) in other.lc.iter() {
-            if ll_lookup(&mut cur_a_ll, *k) == LookupAction::Add {
-                let t = cur_a_ll.peek_next().unwrap();
-                t.1 -= *v;
-                if t.1.is_zero() {
-                    cur_a_ll.remove();
-                }
-            } else {
-                cur_a_ll.insert((*k, -*v))
-            }
-        }
+        self.lc = lc_merge(&self.lc, &other.lc, |v| -v);
+        self.compact_if_exceeds_limit();
     }
 }
 
@@ -571,4 +598,31 @@ mod num_test {
         let c = a + n_b;
         assert!(c.get_value().unwrap() == n_a + n_b);
     }
+
+    #[test]
+    fn lc_limit() {
+        let mut rng = thread_rng();
+        let cs = &crate::core::cs::TestCS::<Fr>::new();
+        let values = (0..100).map(|_| rng.gen()).collect::<Vec<Num<Fr>>>();
+        let signals = values
+            .iter()
+            .map(|v| CNum::alloc(cs, Some(v)))
+            .collect::<Vec<_>>();
+        let sum = values.iter().fold(Num::zero(), |acc, v| acc + v);
+
+        let wide = signals[1..]
+            .iter()
+            .fold(signals[0].clone(), |acc, s| acc + s);
+        assert!(wide.capacity() == 100);
+        assert!(cs.num_constraints() == 0);
+
+        cs.set_lc_limit(Some(16));
+        let narrow = signals[1..]
+            .iter()
+            .fold(signals[0].clone(), |acc, s| acc + s);
+        assert!(narrow.capacity() <= 16);
+        assert!(cs.num_constraints() == 6);
+        assert!(narrow.get_value().unwrap() == sum);
+        (&wide - &narrow).assert_zero();
+    }
 }
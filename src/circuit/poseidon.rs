@@ -51,6 +51,7 @@ pub fn c_poseidon<'a, CS: ConstraintSystem>(
     );
     assert!(n_inputs > 0, "number of inputs should be positive nonzero");
     let cs = inputs[0].cs;
+    let _ns = cs.namespace("poseidon");
     let mut state = vec![CNum::zero(cs); params.t];
     (&mut state[0..n_inputs]).clone_from_slice(inputs);
 
@@ -87,11 +88,27 @@ pub fn c_poseidon_with_salt<'a, CS: ConstraintSystem>(
     c_poseidon(&inputs, params)
 }
 
+pub fn c_poseidon_chain<'a, CS: ConstraintSystem>(
+    cs: &'a CS,
+    inputs: &[CNum<'a, CS>],
+    params: &PoseidonParams<CS::F>,
+) -> CNum<'a, CS> {
+    assert!(params.t > 1, "t should be greater than 1");
+    let mut res = CNum::from_const(cs, &Num::from(inputs.len() as u64));
+    for i in (0..inputs.len().max(1)).step_by(params.t - 1) {
+        let mut state = vec![res];
+        state.extend_from_slice(&inputs[i..(i + params.t - 1).min(inputs.len())]);
+        res = c_poseidon(&state, params);
+    }
+    res
+}
+
 pub fn c_poseidon_merkle_proof_root<'a, CS: ConstraintSystem, L: Unsigned>(
     leaf: &CNum<'a, CS>,
     proof: &CMerkleProof<'a, CS, L>,
     params: &PoseidonParams<CS::F>,
 ) -> CNum<'a, CS> {
+    let _ns = leaf.cs.namespace("poseidon_merkle_proof");
     let mut root = leaf.clone();
     for (p, s) in proof.path.iter().zip(proof.sibling.iter()) {
         let first = s.switch(p, &root);
@@ -108,6 +125,7 @@ pub fn c_poseidon_merkle_tree_root<'a, CS: ConstraintSystem>(
     let leaf_sz = leaf.len();
     assert!(leaf_sz > 0, "should be at least one leaf in the tree");
     let cs = leaf[0].cs;
+    let _ns = cs.namespace("poseidon_merkle_tree");
     let proof_sz = std::mem::size_of::<usize>() * 8 - (leaf_sz - 1).leading_zeros() as usize;
     let total_leaf_sz = 1usize << proof_sz;
     let mut state = leaf.to_vec();
@@ -123,6 +141,7 @@ pub fn c_poseidon_merkle_tree_root<'a, CS: ConstraintSystem>(
 #[cfg(test)]
 mod poseidon_test {
     use bellman::pairing::bn256::Fr;
+    use borsh::{BorshDeserialize, BorshSerialize};
     use rand::{thread_rng, Rng};
     use typenum::{U3, U32};
 
@@ -130,9 +149,39 @@ mod poseidon_test {
     use crate::{
         core::cs::TestCS,
         core::signal::Signal,
-        native::poseidon::{poseidon, poseidon_merkle_proof_root, MerkleProof},
+        native::poseidon::{poseidon, poseidon_chain, poseidon_merkle_proof_root, MerkleProof},
     };
 
+    #[test]
+    fn test_circuit_poseidon_chain() {
+        let mut rng = thread_rng();
+        let poseidon_params = PoseidonParams::<Fr>::new(3, 8, 53);
+        let cs = &TestCS::<Fr>::new();
+        let data = (0..6).map(|_| rng.gen()).collect::<Vec<Num<Fr>>>();
+        let inputs = data
+            .iter()
+            .map(|x| CNum::alloc(cs, Some(x)))
+            .collect::<Vec<_>>();
+
+        let hashes = (0..=data.len())
+            .map(|n| {
+                let res = c_poseidon_chain(cs, &inputs[0..n], &poseidon_params);
+                assert!(res.get_value() == Some(poseidon_chain(&data[0..n], &poseidon_params)));
+                res.get_value().unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert!(cs.check_satisfied().is_ok());
+
+        // the length is hashed, so the trailing zeros change the hash
+        let zeros = vec![Num::zero(); 2];
+        assert!(poseidon_chain(&zeros[0..1], &poseidon_params) != hashes[0]);
+        assert!(
+            poseidon_chain(&zeros, &poseidon_params)
+                != poseidon_chain(&zeros[0..1], &poseidon_params)
+        );
+        assert!((1..hashes.len()).all(|i| hashes[i] != hashes[i - 1]));
+    }
+
     #[test]
     fn test_circuit_poseidon() {
         const N_INPUTS: usize = 3;
@@ -194,5 +243,10 @@ mod poseidon_test {
             n_constraints
         );
         assert!(res.get_value().unwrap() == res2);
+
+        let bin = proof.try_to_vec().unwrap();
+        assert!(bin.len() == PROOF_LENGTH * 33);
+        let proof = MerkleProof::<Fr, U32>::try_from_slice(&bin).unwrap();
+        assert!(poseidon_merkle_proof_root(leaf, &proof, &poseidon_params) == res2);
     }
 }
@@ -0,0 +1,254 @@
+use crate::{
+    circuit::bool::CBool,
+    circuit::uint::{c_wrapping_sum, CUint32, CUint8},
+    core::cs::ConstraintSystem,
+    core::signal::Signal,
+    native::num::Num,
+    native::sha256::{SHA256_IV, SHA256_K},
+};
+
+fn xor3<'a, CS: ConstraintSystem>(
+    a: &CUint32<'a, CS>,
+    b: &CUint32<'a, CS>,
+    c: &CUint32<'a, CS>,
+) -> CUint32<'a, CS> {
+    a.xor(b).xor(c)
+}
+
+// e ? f : g, costs one constraint per bit
+fn ch<'a, CS: ConstraintSystem>(
+    e: &CUint32<'a, CS>,
+    f: &CUint32<'a, CS>,
+    g: &CUint32<'a, CS>,
+) -> CUint32<'a, CS> {
+    CUint32::from_bits(
+        e.bits
+            .iter()
+            .zip(f.bits.iter().zip(g.bits.iter()))
+            .map(|(e, (f, g))| (&g.0 + &e.0 * &(&f.0 - &g.0)).into_bool())
+            .collect(),
+    )
+}
+
+// majority of the bits, costs one constraint per bit. For s = a + b + c the constraint
+// (s - 4m) * (s - 1) = -2m has the only solution m = maj, it is m = 0 for s = 0, 1 and
+// m = 1 for s = 2, 3. The bits with a constant fall back to ab + c(a + b - 2ab), which is free
+// or costs one constraint.
+fn maj<'a, CS: ConstraintSystem>(
+    a: &CUint32<'a, CS>,
+    b: &CUint32<'a, CS>,
+    c: &CUint32<'a, CS>,
+) -> CUint32<'a, CS> {
+    CUint32::from_bits(
+        a.bits
+            .iter()
+            .zip(b.bits.iter().zip(c.bits.iter()))
+            .map(|(a, (b, c))| {
+                if a.as_const().is_some() || b.as_const().is_some() || c.as_const().is_some() {
+                    let ab = &a.0 * &b.0;
+                    return (&ab + &c.0 * &(&a.0 + &b.0 - &ab - &ab)).into_bool();
+                }
+                let value = a.get_value().and_then(|a| {
+                    Some((a as u8) + (b.get_value()? as u8) + (c.get_value()? as u8) >= 2)
+                });
+                let m = a.derive_alloc::<CBool<_>>(value.as_ref());
+                let s = &a.0 + &b.0 + &c.0;
+                a.get_cs().enforce(
+                    &(&s - &m.0 * num!(4)),
+                    &(&s - Num::one()),
+                    &(&m.0 * -num!(2)),
+                );
+                m
+            })
+            .collect(),
+    )
+}
+
+// the block is 512 message bits, the most significant bit of each byte goes first
+pub fn c_sha256_compress<'a, CS: ConstraintSystem>(
+    state: &[CUint32<'a, CS>],
+    block: &[CBool<'a, CS>],
+) -> Vec<CUint32<'a, CS>> {
+    assert!(state.len() == 8, "Wrong length of state");
+    assert!(block.len() == 512, "Wrong length of block");
+    let cs = state[0].get_cs();
+    let _ns = cs.namespace("sha256_compress");
+
+    let mut w = block
+        .chunks(32)
+        .map(|chunk| CUint32::from_bits(chunk.iter().rev().cloned().collect()))
+        .collect::<Vec<_>>();
+    for i in 16..64 {
+        let s0 = xor3(
+            &w[i - 15].rotate_right(7),
+            &w[i - 15].rotate_right(18),
+            &w[i - 15].shr(3),
+        );
+        let s1 = xor3(
+            &w[i - 2].rotate_right(17),
+            &w[i - 2].rotate_right(19),
+            &w[i - 2].shr(10),
+        );
+        let next = c_wrapping_sum(&[w[i - 16].clone(), s0, w[i - 7].clone(), s1]);
+        w.push(next);
+    }
+
+    let mut s = state.to_vec();
+    for i in 0..64 {
+        let (a, b, c, d) = (&s[0], &s[1], &s[2], &s[3]);
+        let (e, f, g, h) = (&s[4], &s[5], &s[6], &s[7]);
+        let s1 = xor3(&e.rotate_right(6), &e.rotate_right(11), &e.rotate_right(25));
+        let s0 = xor3(&a.rotate_right(2), &a.rotate_right(13), &a.rotate_right(22));
+        let t1 = vec![
+            h.clone(),
+            s1,
+            ch(e, f, g),
+            CUint32::from_const(cs, &SHA256_K[i]),
+            w[i].clone(),
+        ];
+        let mut new_a = t1.clone();
+        new_a.extend_from_slice(&[s0, maj(a, b, c)]);
+        let mut new_e = t1;
+        new_e.push(d.clone());
+        s = vec![
+            c_wrapping_sum(&new_a),
+            a.clone(),
+            b.clone(),
+            c.clone(),
+            c_wrapping_sum(&new_e),
+            e.clone(),
+            f.clone(),
+            g.clone(),
+        ];
+    }
+
+    state
+        .iter()
+        .zip(s.iter())
+        .map(|(x, y)| x.wrapping_add(y))
+        .collect()
+}
+
+// hash of the message bits, the most significant bit of each byte goes first
+pub fn c_sha256<'a, CS: ConstraintSystem>(
+    cs: &'a CS,
+    data: &[CBool<'a, CS>],
+) -> Vec<CBool<'a, CS>> {
+    let _ns = cs.namespace("sha256");
+    let len = data.len() as u64;
+    let mut bits = data.to_vec();
+    bits.push(CBool::c_true(cs));
+    while bits.len() % 512 != 448 {
+        bits.push(CBool::c_false(cs));
+    }
+    bits.extend(
+        (0..64)
+            .rev()
+            .map(|i| CBool::from_const(cs, &((len >> i) & 1 == 1))),
+    );
+
+    let mut state = SHA256_IV
+        .iter()
+        .map(|v| CUint32::from_const(cs, v))
+        .collect::<Vec<_>>();
+    for block in bits.chunks(512) {
+        state = c_sha256_compress(&state, block);
+    }
+    state
+        .iter()
+        .flat_map(|s| s.bits.iter().rev().cloned())
+        .collect()
+}
+
+pub fn c_sha256_bytes<'a, CS: ConstraintSystem>(
+    cs: &'a CS,
+    data: &[CUint8<'a, CS>],
+) -> Vec<CUint8<'a, CS>> {
+    let bits = data
+        .iter()
+        .flat_map(|b| b.bits.iter().rev().cloned())
+        .collect::<Vec<_>>();
+    c_sha256(cs, &bits)
+        .chunks(8)
+        .map(|chunk| CUint8::from_bits(chunk.iter().rev().cloned().collect()))
+        .collect()
+}
+
+#[cfg(test)]
+mod sha256_test {
+    use bellman::pairing::bn256::Fr;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::{core::cs::TestCS, native::sha256::sha256};
+
+    #[test]
+    fn test_sha256() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let data = (0..3).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let signal = data
+            .iter()
+            .map(|b| CUint8::alloc(cs, Some(b)))
+            .collect::<Vec<_>>();
+        let res = c_sha256_bytes(cs, &signal);
+        assert!(res
+            .iter()
+            .map(|b| b.get_value().unwrap())
+            .eq(sha256(&data).iter().cloned()));
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_sha256_two_blocks() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let data = (0..100).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let signal = data
+            .iter()
+            .map(|b| CUint8::alloc(cs, Some(b)))
+            .collect::<Vec<_>>();
+        let res = c_sha256_bytes(cs, &signal);
+        assert!(res
+            .iter()
+            .map(|b| b.get_value().unwrap())
+            .eq(sha256(&data).iter().cloned()));
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_maj() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let (a, b, c) = (rng.gen::<u32>(), rng.gen::<u32>(), rng.gen::<u32>());
+        let signals = [a, b, c]
+            .iter()
+            .map(|v| CUint32::alloc(cs, Some(v)))
+            .collect::<Vec<_>>();
+        let n = cs.num_constraints();
+        let res = maj(&signals[0], &signals[1], &signals[2]);
+        assert!(cs.num_constraints() - n == 32);
+        assert!(res.get_value() == Some((a & b) ^ (a & c) ^ (b & c)));
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    // the constants are folded, so the padding of multiple blocks is checked without constraints
+    #[test]
+    fn test_sha256_const() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        for &len in [0, 55, 56, 64, 100].iter() {
+            let data = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let signal = data
+                .iter()
+                .map(|b| CUint8::from_const(cs, b))
+                .collect::<Vec<_>>();
+            let res = c_sha256_bytes(cs, &signal);
+            assert!(res
+                .iter()
+                .map(|b| b.as_const().unwrap())
+                .eq(sha256(&data).iter().cloned()));
+        }
+        assert!(cs.num_constraints() == 0);
+    }
+}
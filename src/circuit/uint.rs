@@ -0,0 +1,364 @@
+use std::{
+    marker::PhantomData,
+    ops::{BitAnd, BitOr, BitXor, Not},
+};
+
+use crate::{
+    circuit::bitify::{c_from_bits_le, c_into_bits_le},
+    circuit::bool::CBool,
+    circuit::num::CNum,
+    core::cs::ConstraintSystem,
+    core::signal::Signal,
+    native::num::Num,
+};
+
+// native unsigned integers represented by CUint
+pub trait UintValue: Copy + Default + Eq + Into<u64> {
+    const BITS: usize;
+
+    fn truncate(value: u64) -> Self;
+}
+
+impl UintValue for u8 {
+    const BITS: usize = 8;
+
+    fn truncate(value: u64) -> Self {
+        value as u8
+    }
+}
+
+impl UintValue for u32 {
+    const BITS: usize = 32;
+
+    fn truncate(value: u64) -> Self {
+        value as u32
+    }
+}
+
+impl UintValue for u64 {
+    const BITS: usize = 64;
+
+    fn truncate(value: u64) -> Self {
+        value
+    }
+}
+
+// little endian bits of an unsigned integer, the arithmetic operations wrap around like the native ones
+#[derive(Clone)]
+pub struct CUint<'a, CS: ConstraintSystem, T: UintValue> {
+    pub bits: Vec<CBool<'a, CS>>,
+    phantom: PhantomData<T>,
+}
+
+pub type CUint8<'a, CS> = CUint<'a, CS, u8>;
+pub type CUint32<'a, CS> = CUint<'a, CS, u32>;
+pub type CUint64<'a, CS> = CUint<'a, CS, u64>;
+
+fn from_bits_value<T: UintValue>(bits: &[bool]) -> T {
+    T::truncate(
+        bits.iter()
+            .rev()
+            .fold(0u64, |acc, &b| (acc << 1) | b as u64),
+    )
+}
+
+impl<'a, CS: ConstraintSystem, T: UintValue> Signal<'a, CS> for CUint<'a, CS, T> {
+    type Value = T;
+
+    fn get_cs(&self) -> &'a CS {
+        self.bits[0].get_cs()
+    }
+
+    fn from_const(cs: &'a CS, value: &Self::Value) -> Self {
+        let value: u64 = (*value).into();
+        Self::from_bits(
+            (0..T::BITS)
+                .map(|i| CBool::from_const(cs, &((value >> i) & 1 == 1)))
+                .collect(),
+        )
+    }
+
+    fn get_value(&self) -> Option<Self::Value> {
+        let bits = self
+            .bits
+            .iter()
+            .map(|b| b.get_value())
+            .collect::<Option<Vec<_>>>()?;
+        Some(from_bits_value(&bits))
+    }
+
+    fn as_const(&self) -> Option<Self::Value> {
+        let bits = self
+            .bits
+            .iter()
+            .map(|b| b.as_const())
+            .collect::<Option<Vec<_>>>()?;
+        Some(from_bits_value(&bits))
+    }
+
+    fn alloc(cs: &'a CS, value: Option<&Self::Value>) -> Self {
+        let value = value.map(|&v| Num::from(v.into()));
+        Self::from_num(&CNum::alloc(cs, value.as_ref()))
+    }
+
+    fn switch(&self, bit: &CBool<'a, CS>, if_else: &Self) -> Self {
+        self.map_bits(if_else, |t, f| t.switch(bit, f))
+    }
+
+    fn assert_const(&self, value: &Self::Value) {
+        self.into_num().assert_const(&Num::from((*value).into()));
+    }
+
+    fn assert_eq(&self, other: &Self) {
+        self.into_num().assert_eq(&other.into_num());
+    }
+
+    fn is_eq(&self, other: &Self) -> CBool<'a, CS> {
+        self.into_num().is_eq(&other.into_num())
+    }
+
+    fn inputize(&self) {
+        self.into_num().inputize();
+    }
+
+    fn linearize_builder(&self, acc: &mut Vec<CNum<'a, CS>>) {
+        acc.push(self.into_num());
+    }
+}
+
+impl<'a, CS: ConstraintSystem, T: UintValue> CUint<'a, CS, T> {
+    pub fn from_bits(bits: Vec<CBool<'a, CS>>) -> Self {
+        assert!(bits.len() == T::BITS, "Wrong number of bits");
+        Self {
+            bits,
+            phantom: PhantomData,
+        }
+    }
+
+    // the signal should be less than 2^BITS
+    pub fn from_num(signal: &CNum<'a, CS>) -> Self {
+        Self::from_bits(c_into_bits_le(signal, T::BITS))
+    }
+
+    #[inline]
+    pub fn into_num(&self) -> CNum<'a, CS> {
+        c_from_bits_le(&self.bits)
+    }
+
+    // low bits of the signal, which should be less than 2^limit
+    fn from_num_truncated(signal: &CNum<'a, CS>, limit: usize) -> Self {
+        let mut bits = c_into_bits_le(signal, limit);
+        bits.truncate(T::BITS);
+        Self::from_bits(bits)
+    }
+
+    fn map_bits(
+        &self,
+        other: &Self,
+        f: impl Fn(&CBool<'a, CS>, &CBool<'a, CS>) -> CBool<'a, CS>,
+    ) -> Self {
+        Self::from_bits(
+            self.bits
+                .iter()
+                .zip(other.bits.iter())
+                .map(|(a, b)| f(a, b))
+                .collect(),
+        )
+    }
+}
+
+// the arithmetic operations cost one constraint per bit of the intermediate sum or product, the
+// bitwise ones cost at most one constraint per bit and the shifts and rotations are free
+impl<'a, CS: ConstraintSystem, T: UintValue> CUint<'a, CS, T> {
+    pub fn wrapping_add(&self, other: &Self) -> Self {
+        c_wrapping_sum(&[self.clone(), other.clone()])
+    }
+
+    pub fn wrapping_sub(&self, other: &Self) -> Self {
+        let _ns = self.get_cs().namespace("uint_sub");
+        let k = Num::one() + Num::from(u64::MAX >> (64 - T::BITS));
+        Self::from_num_truncated(&(self.into_num() + k - other.into_num()), T::BITS + 1)
+    }
+
+    pub fn wrapping_mul(&self, other: &Self) -> Self {
+        let _ns = self.get_cs().namespace("uint_mul");
+        Self::from_num_truncated(&(self.into_num() * other.into_num()), 2 * T::BITS)
+    }
+
+    pub fn and(&self, other: &Self) -> Self {
+        self.map_bits(other, |a, b| a.and(b))
+    }
+
+    pub fn or(&self, other: &Self) -> Self {
+        self.map_bits(other, |a, b| a.or(b))
+    }
+
+    pub fn xor(&self, other: &Self) -> Self {
+        self.map_bits(other, |a, b| a.xor(b))
+    }
+
+    pub fn not(&self) -> Self {
+        Self::from_bits(self.bits.iter().map(|b| b.not()).collect())
+    }
+
+    pub fn shl(&self, n: usize) -> Self {
+        let zero = CBool::c_false(self.get_cs());
+        Self::from_bits(
+            (0..T::BITS)
+                .map(|i| {
+                    if i < n {
+                        zero.clone()
+                    } else {
+                        self.bits[i - n].clone()
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    pub fn shr(&self, n: usize) -> Self {
+        let zero = CBool::c_false(self.get_cs());
+        Self::from_bits(
+            (0..T::BITS)
+                .map(|i| {
+                    self.bits
+                        .get(i + n)
+                        .cloned()
+                        .unwrap_or_else(|| zero.clone())
+                })
+                .collect(),
+        )
+    }
+
+    pub fn rotate_left(&self, n: usize) -> Self {
+        let mut bits = self.bits.clone();
+        bits.rotate_right(n % T::BITS);
+        Self::from_bits(bits)
+    }
+
+    pub fn rotate_right(&self, n: usize) -> Self {
+        let mut bits = self.bits.clone();
+        bits.rotate_left(n % T::BITS);
+        Self::from_bits(bits)
+    }
+}
+
+// wrapping sum of several integers with a single decomposition
+pub fn c_wrapping_sum<'a, CS: ConstraintSystem, T: UintValue>(
+    items: &[CUint<'a, CS, T>],
+) -> CUint<'a, CS, T> {
+    assert!(!items.is_empty(), "should be at least one item");
+    let _ns = items[0].get_cs().namespace("uint_sum");
+    let carry_bits = std::mem::size_of::<usize>() * 8 - (items.len() - 1).leading_zeros() as usize;
+    let sum = items
+        .iter()
+        .skip(1)
+        .fold(items[0].into_num(), |acc, item| acc + item.into_num());
+    CUint::from_num_truncated(&sum, T::BITS + carry_bits)
+}
+
+impl<'a, CS: ConstraintSystem, T: UintValue> Not for CUint<'a, CS, T> {
+    type Output = CUint<'a, CS, T>;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        CUint::not(&self)
+    }
+}
+
+forward_unop_ex!(impl<'a, CS: ConstraintSystem, T: UintValue> Not for CUint<'a, CS, T>, not);
+
+impl<'l, 'a, CS: ConstraintSystem, T: UintValue> BitAnd<&'l CUint<'a, CS, T>> for CUint<'a, CS, T> {
+    type Output = CUint<'a, CS, T>;
+
+    #[inline]
+    fn bitand(self, other: &'l CUint<'a, CS, T>) -> Self::Output {
+        self.and(other)
+    }
+}
+
+impl<'l, 'a, CS: ConstraintSystem, T: UintValue> BitOr<&'l CUint<'a, CS, T>> for CUint<'a, CS, T> {
+    type Output = CUint<'a, CS, T>;
+
+    #[inline]
+    fn bitor(self, other: &'l CUint<'a, CS, T>) -> Self::Output {
+        self.or(other)
+    }
+}
+
+impl<'l, 'a, CS: ConstraintSystem, T: UintValue> BitXor<&'l CUint<'a, CS, T>> for CUint<'a, CS, T> {
+    type Output = CUint<'a, CS, T>;
+
+    #[inline]
+    fn bitxor(self, other: &'l CUint<'a, CS, T>) -> Self::Output {
+        self.xor(other)
+    }
+}
+
+forward_all_binop_to_val_ref_ex!(impl<'a, CS: ConstraintSystem, T: UintValue> BitAnd<CUint<'a, CS, T>> for CUint<'a, CS, T>, bitand -> CUint<'a, CS, T>);
+forward_all_binop_to_val_ref_ex!(impl<'a, CS: ConstraintSystem, T: UintValue> BitOr<CUint<'a, CS, T>> for CUint<'a, CS, T>, bitor -> CUint<'a, CS, T>);
+forward_all_binop_to_val_ref_ex!(impl<'a, CS: ConstraintSystem, T: UintValue> BitXor<CUint<'a, CS, T>> for CUint<'a, CS, T>, bitxor -> CUint<'a, CS, T>);
+
+#[cfg(test)]
+mod uint_test {
+    use bellman::pairing::bn256::Fr;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::core::cs::TestCS;
+
+    macro_rules! check_ops {
+        ($cs:expr, $rng:expr, $t:ty) => {{
+            let (a, b, c): ($t, $t, $t) = ($rng.gen(), $rng.gen(), $rng.gen());
+            let n = $rng.gen::<usize>() % <$t>::BITS as usize;
+            let sa = CUint::<_, $t>::alloc($cs, Some(&a));
+            let sb = CUint::<_, $t>::alloc($cs, Some(&b));
+            let sc = CUint::<_, $t>::from_const($cs, &c);
+
+            assert!(sa.get_value() == Some(a) && sc.as_const() == Some(c));
+            assert!(sa.wrapping_add(&sb).get_value() == Some(a.wrapping_add(b)));
+            assert!(sa.wrapping_add(&sc).get_value() == Some(a.wrapping_add(c)));
+            assert!(sa.wrapping_sub(&sb).get_value() == Some(a.wrapping_sub(b)));
+            assert!(sc.wrapping_sub(&sa).get_value() == Some(c.wrapping_sub(a)));
+            assert!(sa.wrapping_mul(&sb).get_value() == Some(a.wrapping_mul(b)));
+            let sum = c_wrapping_sum(&[sa.clone(), sb.clone(), sc.clone()]);
+            assert!(sum.get_value() == Some(a.wrapping_add(b).wrapping_add(c)));
+            assert!((&sa & &sb).get_value() == Some(a & b));
+            assert!((&sa | &sc).get_value() == Some(a | c));
+            assert!((&sa ^ &sb).get_value() == Some(a ^ b));
+            assert!((!&sa).get_value() == Some(!a));
+            assert!(sa.shl(n).get_value() == Some(a << n));
+            assert!(sa.shr(n).get_value() == Some(a >> n));
+            assert!(sa.rotate_left(n).get_value() == Some(a.rotate_left(n as u32)));
+            assert!(sa.rotate_right(n).get_value() == Some(a.rotate_right(n as u32)));
+            assert!(sa.is_eq(&sb).get_value() == Some(a == b));
+            assert!(sa.is_eq(&sa.clone()).get_value() == Some(true));
+            assert!(sc.wrapping_add(&sc).as_const() == Some(c.wrapping_add(c)));
+        }};
+    }
+
+    #[test]
+    fn test_uint_ops() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        for _ in 0..10 {
+            check_ops!(cs, rng, u8);
+            check_ops!(cs, rng, u32);
+            check_ops!(cs, rng, u64);
+        }
+
+        let a = CUint8::alloc(cs, Some(&u8::MAX));
+        let b = CUint8::alloc(cs, Some(&1));
+        assert!(a.wrapping_add(&b).get_value() == Some(0));
+        assert!(b.wrapping_sub(&a).get_value() == Some(2));
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_uint_alloc_range() {
+        let cs = &TestCS::<Fr>::new_collecting();
+        let n = CNum::alloc(cs, Some(&num!(256)));
+        CUint8::from_num(&n);
+        assert!(cs.check_satisfied().is_err());
+    }
+}
@@ -0,0 +1,279 @@
+use std::{cell::RefCell, fmt};
+
+use crate::{
+    circuit::num::{CNum, Index},
+    core::cs::{Circuit, ConstraintSystem},
+    core::field::Field,
+    core::r1cs::{Constraint, LinearCombination, R1CS},
+    native::num::Num,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnderConstrainedKind {
+    // the variable is not used in any constraint
+    Unconstrained,
+    // the variable is used in the only constraint with the given index, which could be satisfied
+    // by the variable for any values of the other variables
+    Free(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnderConstrained {
+    pub index: Index,
+    pub namespace: String,
+    pub kind: UnderConstrainedKind,
+}
+
+impl fmt::Display for UnderConstrained {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            UnderConstrainedKind::Unconstrained => write!(
+                f,
+                "{:?} allocated at '{}' is not used in any constraint",
+                self.index, self.namespace
+            ),
+            UnderConstrainedKind::Free(i) => write!(
+                f,
+                "{:?} allocated at '{}' is used only in constraint {}, which it satisfies freely",
+                self.index, self.namespace, i
+            ),
+        }
+    }
+}
+
+pub struct AnalysisCS<F: Field> {
+    pub r1cs: RefCell<R1CS<F>>,
+    pub namespace: RefCell<Vec<String>>,
+    // the namespace of every aux variable
+    pub aux_namespace: RefCell<Vec<String>>,
+    pub lc_limit: RefCell<Option<usize>>,
+}
+
+impl<F: Field> AnalysisCS<F> {
+    pub fn new() -> Self {
+        Self {
+            r1cs: RefCell::new(R1CS::new()),
+            namespace: RefCell::new(vec![]),
+            aux_namespace: RefCell::new(vec![]),
+            lc_limit: RefCell::new(None),
+        }
+    }
+
+    pub fn find_under_constrained<C: Circuit<F = F>>(c: &C) -> Vec<UnderConstrained> {
+        let cs = Self::new();
+        c.synthesize(&cs);
+        cs.under_constrained()
+    }
+
+    pub fn under_constrained(&self) -> Vec<UnderConstrained> {
+        let aux_namespace = self.aux_namespace.borrow();
+        under_constrained(&self.r1cs.borrow())
+            .into_iter()
+            .map(|(n, kind)| UnderConstrained {
+                index: Index::Aux(n),
+                namespace: aux_namespace[n].clone(),
+                kind,
+            })
+            .collect()
+    }
+}
+
+impl<F: Field> Default for AnalysisCS<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> Clone for AnalysisCS<F> {
+    fn clone(&self) -> Self {
+        panic!("Clone is not implemented for AnalysisCS")
+    }
+}
+
+impl<F: Field> ConstraintSystem for AnalysisCS<F> {
+    type F = F;
+
+    fn alloc(&self, _: Option<Num<Self::F>>) -> Index {
+        let mut r1cs = self.r1cs.borrow_mut();
+        let index = Index::Aux(r1cs.num_aux);
+        r1cs.num_aux += 1;
+        self.aux_namespace
+            .borrow_mut()
+            .push(self.namespace.borrow().join("/"));
+        index
+    }
+
+    fn alloc_input(&self, _: Option<Num<Self::F>>) -> Index {
+        let mut r1cs = self.r1cs.borrow_mut();
+        let index = Index::Input(r1cs.num_inputs);
+        r1cs.num_inputs += 1;
+        index
+    }
+
+    fn enforce(&self, a: &CNum<Self>, b: &CNum<Self>, c: &CNum<Self>) {
+        self.r1cs
+            .borrow_mut()
+            .constraints
+            .push(Constraint::new(a, b, c));
+    }
+
+    fn push_namespace(&self, name: &str) {
+        self.namespace.borrow_mut().push(name.to_string());
+    }
+
+    fn pop_namespace(&self) {
+        self.namespace.borrow_mut().pop();
+    }
+
+    fn lc_limit(&self) -> Option<usize> {
+        *self.lc_limit.borrow()
+    }
+
+    fn set_lc_limit(&self, limit: Option<usize>) {
+        *self.lc_limit.borrow_mut() = limit;
+    }
+}
+
+fn coeff<F: Field>(lc: &[(Index, Num<F>)], index: Index) -> Option<Num<F>> {
+    lc.iter()
+        .find(|(i, v)| *i == index && !v.is_zero())
+        .map(|(_, v)| *v)
+}
+
+fn is_const<F: Field>(lc: &[(Index, Num<F>)]) -> bool {
+    lc.iter().all(|(i, _)| *i == Index::Input(0))
+}
+
+fn is_zero<F: Field>(lc: &[(Index, Num<F>)]) -> bool {
+    lc.iter().all(|(_, v)| v.is_zero())
+}
+
+fn without<F: Field>(lc: &[(Index, Num<F>)], index: Index) -> LinearCombination<F> {
+    lc.iter().filter(|(i, _)| *i != index).cloned().collect()
+}
+
+// sufficient conditions for a * b = c to have a solution in x for any values of the other variables
+fn is_free<F: Field>(c: &Constraint<F>, x: Index) -> bool {
+    // with an empty side the constraint is 0 = c, it is linear in x or does not depend on x
+    if is_zero(&c.a) || is_zero(&c.b) {
+        return true;
+    }
+    match (coeff(&c.a, x), coeff(&c.b, x), coeff(&c.c, x)) {
+        // the only linear term
+        (None, None, Some(_)) => true,
+        (Some(_), None, None) => is_const(&c.b),
+        (None, Some(_), None) => is_const(&c.a),
+        // (l + p) * (l + q) = 0 has the roots l = -p and l = -q, like in assert_bit
+        (Some(_), Some(_), None) => {
+            is_zero(&c.c) && without(&c.a, Index::Input(0)) == without(&c.b, Index::Input(0))
+        }
+        _ => false,
+    }
+}
+
+// the aux variables used in no constraints or in the only constraint, which they satisfy freely
+pub fn under_constrained<F: Field>(r1cs: &R1CS<F>) -> Vec<(usize, UnderConstrainedKind)> {
+    let mut usage = vec![vec![]; r1cs.num_aux];
+    for (ci, c) in r1cs.constraints.iter().enumerate() {
+        let mut vars =
+            c.a.iter()
+                .chain(c.b.iter())
+                .chain(c.c.iter())
+                .filter_map(|(i, _)| match i {
+                    Index::Aux(n) => Some(*n),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+        vars.sort();
+        vars.dedup();
+        for n in vars {
+            usage[n].push(ci);
+        }
+    }
+
+    usage
+        .iter()
+        .enumerate()
+        .filter_map(|(n, cons)| match cons.as_slice() {
+            [] => Some((n, UnderConstrainedKind::Unconstrained)),
+            [ci] if is_free(&r1cs.constraints[*ci], Index::Aux(n)) => {
+                Some((n, UnderConstrainedKind::Free(*ci)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod analysis_test {
+    use bellman::pairing::bn256::Fr;
+
+    use super::*;
+    use crate::{
+        circuit::bitify::c_into_bits_le_strict, circuit::bool::CBool, core::signal::Signal,
+    };
+
+    struct IntoBits {
+        assert_bits: bool,
+    }
+
+    impl Circuit for IntoBits {
+        type F = Fr;
+
+        fn synthesize<CS: ConstraintSystem<F = Fr>>(&self, cs: &CS) {
+            let value = CNum::alloc(cs, None);
+            value.inputize();
+            c_into_bits_le_strict(&value);
+
+            let _ns = cs.namespace("into_bits");
+            let mut remained = value.clone();
+            let mut k = Num::one();
+            for _ in 1..8 {
+                k = k.double();
+                let bit = CBool::alloc(cs, None);
+                if self.assert_bits {
+                    bit.assert();
+                }
+                remained -= &bit.0 * k;
+            }
+            remained.assert_bit();
+            CNum::alloc(cs, None);
+        }
+    }
+
+    #[test]
+    fn test_under_constrained() {
+        let res = AnalysisCS::find_under_constrained(&IntoBits { assert_bits: true });
+        assert!(res.len() == 1);
+        assert!(res[0].kind == UnderConstrainedKind::Unconstrained);
+        assert!(res[0].namespace == "into_bits");
+
+        let res = AnalysisCS::find_under_constrained(&IntoBits { assert_bits: false });
+        for r in res.iter() {
+            println!("{}", r);
+        }
+        assert!(res.len() == 8);
+        assert!(res[..7]
+            .iter()
+            .all(|r| matches!(r.kind, UnderConstrainedKind::Free(_))));
+    }
+
+    #[test]
+    fn test_is_free_zero() {
+        let x = Index::Aux(0);
+        let y = Index::Aux(1);
+        let one = Num::<Fr>::one();
+        let constraint = |a: &[Index], b: &[Index], c: &[Index]| Constraint {
+            a: a.iter().map(|i| (*i, one)).collect(),
+            b: b.iter().map(|i| (*i, one)).collect(),
+            c: c.iter().map(|i| (*i, one)).collect(),
+        };
+
+        assert!(is_free(&constraint(&[x], &[], &[y]), x));
+        assert!(is_free(&constraint(&[x], &[], &[x, y]), x));
+        assert!(is_free(&constraint(&[], &[x, y], &[y]), x));
+        assert!(is_free(&constraint(&[Index::Input(0)], &[x], &[y]), x));
+        assert!(!is_free(&constraint(&[y], &[x], &[Index::Input(0)]), x));
+        assert!(!is_free(&constraint(&[x], &[x], &[x]), x));
+    }
+}
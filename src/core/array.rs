@@ -0,0 +1,210 @@
+use std::convert::TryInto;
+
+use crate::{
+    circuit::bool::CBool, circuit::num::CNum, core::cs::ConstraintSystem, core::signal::Signal,
+};
+
+fn to_array<T, const N: usize>(v: Vec<T>) -> [T; N] {
+    match v.try_into() {
+        Ok(res) => res,
+        Err(_) => panic!("Wrong length of array"),
+    }
+}
+
+// the constraint system is taken from the first item, so the empty arrays fail to compile
+struct NonEmpty<const N: usize>;
+
+impl<const N: usize> NonEmpty<N> {
+    const CHECK: () = assert!(N > 0, "signal array should not be empty");
+}
+
+impl<'a, CS: 'a + ConstraintSystem, T: Signal<'a, CS>, const N: usize> Signal<'a, CS> for [T; N] {
+    type Value = [T::Value; N];
+
+    fn get_value(&self) -> Option<Self::Value> {
+        Some(to_array(
+            self.iter()
+                .map(|v| v.get_value())
+                .collect::<Option<Vec<_>>>()?,
+        ))
+    }
+
+    fn switch(&self, bit: &CBool<'a, CS>, if_else: &Self) -> Self {
+        to_array(
+            self.iter()
+                .zip(if_else.iter())
+                .map(|(t, f)| t.switch(bit, f))
+                .collect(),
+        )
+    }
+
+    fn get_cs(&self) -> &'a CS {
+        let () = NonEmpty::<N>::CHECK;
+        self[0].get_cs()
+    }
+
+    fn from_const(cs: &'a CS, value: &Self::Value) -> Self {
+        let () = NonEmpty::<N>::CHECK;
+        to_array(value.iter().map(|v| T::from_const(cs, v)).collect())
+    }
+
+    fn as_const(&self) -> Option<Self::Value> {
+        Some(to_array(
+            self.iter()
+                .map(|v| v.as_const())
+                .collect::<Option<Vec<_>>>()?,
+        ))
+    }
+
+    fn alloc(cs: &'a CS, value: Option<&Self::Value>) -> Self {
+        let () = NonEmpty::<N>::CHECK;
+        match value {
+            Some(value) => to_array(value.iter().map(|v| T::alloc(cs, Some(v))).collect()),
+            _ => to_array((0..N).map(|_| T::alloc(cs, None)).collect()),
+        }
+    }
+
+    fn assert_const(&self, value: &Self::Value) {
+        self.iter()
+            .zip(value.iter())
+            .for_each(|(s, v)| s.assert_const(v));
+    }
+
+    fn inputize(&self) {
+        self.iter().for_each(|s| s.inputize());
+    }
+
+    fn assert_eq(&self, other: &Self) {
+        self.iter()
+            .zip(other.iter())
+            .for_each(|(s, o)| s.assert_eq(o));
+    }
+
+    fn is_eq(&self, other: &Self) -> CBool<'a, CS> {
+        let mut acc = CNum::one(self.get_cs());
+        for i in 0..N {
+            acc *= self[i].is_eq(&other[i]).0;
+        }
+        acc.into_bool()
+    }
+
+    fn linearize_builder(&self, acc: &mut Vec<CNum<'a, CS>>) {
+        self.iter().for_each(|s| s.linearize_builder(acc));
+    }
+}
+
+// serde implements arrays up to 32 items only, use #[serde(with = "serde_array")] for longer ones
+pub mod serde_array {
+    use std::convert::TryInto;
+
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, T: Serialize, const N: usize>(
+        value: &[T; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(value.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[T; N], D::Error> {
+        let v = Vec::<T>::deserialize(deserializer)?;
+        let len = v.len();
+        v.try_into().map_err(|_| {
+            de::Error::invalid_length(len, &format!("an array of length {}", N).as_str())
+        })
+    }
+}
+
+// borsh implements arrays of some lengths only, the encoding is the same: items without length
+pub mod borsh_array {
+    use std::io::{self, Write};
+
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    use super::to_array;
+
+    pub fn serialize<W: Write, T: BorshSerialize, const N: usize>(
+        value: &[T; N],
+        writer: &mut W,
+    ) -> Result<(), io::Error> {
+        for v in value.iter() {
+            BorshSerialize::serialize(v, writer)?;
+        }
+        Ok(())
+    }
+
+    pub fn deserialize<T: BorshDeserialize, const N: usize>(
+        buf: &mut &[u8],
+    ) -> Result<[T; N], io::Error> {
+        Ok(to_array(
+            (0..N)
+                .map(|_| T::deserialize(buf))
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod array_test {
+    use bellman::pairing::bn256::Fr;
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use fawkes_crypto_derive::Signal;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::{core::cs::TestCS, native::num::Num};
+
+    #[derive(Clone, Signal)]
+    #[signal(generate_value = "Path")]
+    struct CPath<'a, CS: ConstraintSystem> {
+        sibling: [CNum<'a, CS>; 40],
+        path: [CBool<'a, CS>; 40],
+    }
+
+    #[test]
+    fn test_array_signal() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let value: [Num<Fr>; 40] = to_array((0..40).map(|_| rng.gen()).collect());
+        let other: [Num<Fr>; 40] = to_array((0..40).map(|_| rng.gen()).collect());
+
+        let signal = <[CNum<TestCS<Fr>>; 40]>::alloc(cs, Some(&value));
+        assert!(signal.get_value() == Some(value));
+        assert!(signal.linearize().len() == 40);
+
+        let const_signal = signal.derive_const::<[CNum<TestCS<Fr>>; 40]>(&other);
+        assert!(const_signal.as_const() == Some(other));
+        let bit = CBool::alloc(cs, Some(&false));
+        let res = signal.switch(&bit, &const_signal);
+        assert!(res.get_value() == Some(other));
+        assert!(res.is_eq(&const_signal).get_value() == Some(true));
+        assert!(res.is_eq(&signal).get_value() == Some(false));
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    #[test]
+    fn test_array_value() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let path = Path::<Fr> {
+            sibling: to_array((0..40).map(|_| rng.gen()).collect()),
+            path: to_array((0..40).map(|_| rng.gen()).collect()),
+        };
+
+        let signal = CPath::alloc(cs, Some(&path));
+        let value = signal.get_value().unwrap();
+        assert!(value.sibling == path.sibling && value.path == path.path);
+
+        let json = serde_json::to_string(&path).unwrap();
+        let path_json = serde_json::from_str::<Path<Fr>>(&json).unwrap();
+        assert!(path_json.sibling == path.sibling && path_json.path == path.path);
+        assert!(serde_json::from_str::<Path<Fr>>(r#"{"sibling":[],"path":[]}"#).is_err());
+
+        let bin = path.try_to_vec().unwrap();
+        assert!(bin.len() == 40 * 32 + 40);
+        let path_bin = Path::<Fr>::try_from_slice(&bin).unwrap();
+        assert!(path_bin.sibling == path.sibling && path_bin.path == path.path);
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    convert::TryFrom,
+    io::{self, Write},
+    iter::*,
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+    slice::SliceIndex,
+    slice::{Iter, IterMut},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{
+    de::{self, Deserialize, Deserializer},
+    ser::{Serialize, Serializer},
+};
+use typenum::Unsigned;
+
+// vector of at most L items, the native value of CVec
+#[derive(Debug, Clone)]
+pub struct BoundedVec<T: Sized, L: Unsigned>(pub Vec<T>, pub PhantomData<L>);
+
+impl<T, L: Unsigned> BoundedVec<T, L> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.0.iter_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.0.get(index)
+    }
+}
+
+impl<T: Serialize, L: Unsigned> Serialize for BoundedVec<T, L> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, L: Unsigned> Deserialize<'de> for BoundedVec<T, L> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BoundedVec<T, L>, D::Error> {
+        let v = Vec::<T>::deserialize(deserializer)?;
+        let len = v.len();
+        Self::try_from(v).map_err(|_| {
+            de::Error::invalid_length(
+                len,
+                &format!("a vector of length at most {}", L::USIZE).as_str(),
+            )
+        })
+    }
+}
+
+// encoded as a borsh vector, the length goes first
+impl<T: BorshSerialize, L: Unsigned> BorshSerialize for BoundedVec<T, L> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        self.0.serialize(writer)
+    }
+}
+
+impl<T: BorshDeserialize, L: Unsigned> BorshDeserialize for BoundedVec<T, L> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, io::Error> {
+        let len = <u32 as BorshDeserialize>::deserialize(buf)? as usize;
+        if len > L::USIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Wrong length of BoundedVec",
+            ));
+        }
+        let v = (0..len)
+            .map(|_| T::deserialize(buf))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self(v, PhantomData))
+    }
+}
+
+// returns the vector back if it is too long
+impl<T, L: Unsigned> TryFrom<Vec<T>> for BoundedVec<T, L> {
+    type Error = Vec<T>;
+
+    fn try_from(v: Vec<T>) -> Result<Self, Self::Error> {
+        if v.len() <= L::USIZE {
+            Ok(Self(v, PhantomData))
+        } else {
+            Err(v)
+        }
+    }
+}
+
+impl<T, L: Unsigned> Default for BoundedVec<T, L> {
+    fn default() -> Self {
+        Self(vec![], PhantomData)
+    }
+}
+
+impl<T, L: Unsigned> FromIterator<T> for BoundedVec<T, L> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let v = Vec::<T>::from_iter(iter);
+        assert!(v.len() <= L::USIZE, "Wrong length of BoundedVec");
+        Self(v, PhantomData)
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, L: Unsigned> Index<I> for BoundedVec<T, L> {
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &Self::Output {
+        Index::index(&*self.0, index)
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, L: Unsigned> IndexMut<I> for BoundedVec<T, L> {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        IndexMut::index_mut(&mut *self.0, index)
+    }
+}
+
+#[cfg(test)]
+mod boundedvec_test {
+    use borsh::BorshDeserialize;
+    use typenum::U3;
+
+    use super::*;
+
+    #[test]
+    fn test_boundedvec_length() {
+        let v = BoundedVec::<u32, U3>::try_from(vec![1, 2]).unwrap();
+        assert!(v.0 == vec![1, 2]);
+        assert!(BoundedVec::<u32, U3>::try_from(vec![1, 2, 3, 4]).unwrap_err() == vec![1, 2, 3, 4]);
+
+        assert!(
+            serde_json::from_str::<BoundedVec<u32, U3>>("[1,2]")
+                .unwrap()
+                .0
+                == v.0
+        );
+        assert!(serde_json::from_str::<BoundedVec<u32, U3>>("[]").is_ok());
+        assert!(serde_json::from_str::<BoundedVec<u32, U3>>("[1,2,3,4]").is_err());
+
+        let bin = v.try_to_vec().unwrap();
+        assert!(bin.len() == 12);
+        assert!(BoundedVec::<u32, U3>::try_from_slice(&bin).unwrap().0 == v.0);
+        assert!(BoundedVec::<u32, U3>::try_from_slice(&bin[..8]).is_err());
+        let long = vec![1u32, 2, 3, 4].try_to_vec().unwrap();
+        assert!(BoundedVec::<u32, U3>::try_from_slice(&long).is_err());
+    }
+}
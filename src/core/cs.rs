@@ -1,8 +1,10 @@
-use std::{cell::RefCell, collections::HashMap};
+use std::{cell::RefCell, collections::HashMap, fmt};
 
 use crate::{
     circuit::num::{CNum, Index},
     core::field::Field,
+    core::r1cs::{Constraint, Witness, R1CS},
+    error::Error,
     native::num::Num,
 };
 
@@ -12,6 +14,39 @@ pub trait ConstraintSystem: Sized + Clone {
     fn alloc(&self, value: Option<Num<Self::F>>) -> Index;
     fn alloc_input(&self, value: Option<Num<Self::F>>) -> Index;
     fn enforce(&self, a: &CNum<Self>, b: &CNum<Self>, c: &CNum<Self>);
+
+    fn try_alloc(&self, value: Option<Num<Self::F>>) -> Result<Index, Error> {
+        Ok(self.alloc(value))
+    }
+
+    fn try_alloc_input(&self, value: Option<Num<Self::F>>) -> Result<Index, Error> {
+        Ok(self.alloc_input(value))
+    }
+
+    fn push_namespace(&self, _name: &str) {}
+    fn pop_namespace(&self) {}
+
+    // linear combinations wider than the limit are replaced with a fresh variable and one
+    // constraint, see CNum::compact. Set it at the beginning of Circuit::synthesize, so the
+    // keys, the witness and the proof are built for the same constraints
+    fn lc_limit(&self) -> Option<usize> {
+        None
+    }
+    fn set_lc_limit(&self, _limit: Option<usize>) {}
+
+    // the namespace is closed when the returned guard is dropped
+    fn namespace(&self, name: &str) -> Namespace<'_, Self> {
+        self.push_namespace(name);
+        Namespace(self)
+    }
+}
+
+pub struct Namespace<'a, CS: ConstraintSystem>(&'a CS);
+
+impl<'a, CS: ConstraintSystem> Drop for Namespace<'a, CS> {
+    fn drop(&mut self) {
+        self.0.pop_namespace();
+    }
 }
 
 pub trait Circuit {
@@ -19,15 +54,54 @@ pub trait Circuit {
 
     fn synthesize<CS: ConstraintSystem<F = Self::F>>(&self, cs: &CS);
 
-    fn get_inputs(&self) -> Option<Vec<Num<Self::F>>>;
+    // values of the signals passed to inputize, in order, by a synthesis with WitnessCS
+    fn get_inputs(&self) -> Option<Vec<Num<Self::F>>>
+    where
+        Self: Sized,
+    {
+        let cs = WitnessCS::new();
+        self.synthesize(&cs);
+        cs.inputs.into_inner().into_iter().skip(1).collect()
+    }
 }
 
+#[derive(Clone, Debug)]
+pub struct UnsatisfiedConstraint<F: Field> {
+    pub index: usize,
+    pub namespace: String,
+    pub a: Num<F>,
+    pub b: Num<F>,
+    pub c: Num<F>,
+    pub constraint: Constraint<F>,
+}
+
+impl<F: Field> fmt::Display for UnsatisfiedConstraint<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "constraint {} at '{}' is not satisfied: a = {}, b = {}, c = {}, a * b = {}",
+            self.index,
+            self.namespace,
+            self.a.to_string(),
+            self.b.to_string(),
+            self.c.to_string(),
+            (self.a * self.b).to_string()
+        )
+    }
+}
+
+// alloc numbers the witness variables as Index::Aux(0), Index::Aux(1), ... with the naux counter,
+// like the R1CS and the prover do, and alloc_input numbers the inputs from Index::Input(1), the
+// input 0 being the constant one
 pub struct TestCS<F: Field> {
     pub ninputs: RefCell<usize>,
     pub naux: RefCell<usize>,
     pub ncons: RefCell<usize>,
     pub variables: RefCell<HashMap<Index, Num<F>>>,
-    f: std::marker::PhantomData<F>,
+    pub namespace: RefCell<Vec<String>>,
+    pub unsatisfied: RefCell<Vec<UnsatisfiedConstraint<F>>>,
+    pub lc_limit: RefCell<Option<usize>>,
+    collect_unsatisfied: bool,
 }
 
 impl<F: Field> TestCS<F> {
@@ -37,13 +111,39 @@ impl<F: Field> TestCS<F> {
             naux: RefCell::new(0),
             ncons: RefCell::new(0),
             variables: RefCell::new(HashMap::new()),
-            f: std::marker::PhantomData,
+            namespace: RefCell::new(vec![]),
+            unsatisfied: RefCell::new(vec![]),
+            lc_limit: RefCell::new(None),
+            collect_unsatisfied: false,
+        }
+    }
+
+    // collect unsatisfied constraints instead of panicking, see check_satisfied
+    pub fn new_collecting() -> Self {
+        Self {
+            collect_unsatisfied: true,
+            ..Self::new()
         }
     }
 
     pub fn num_constraints(&self) -> usize {
         *self.ncons.borrow()
     }
+
+    pub fn check_satisfied(&self) -> Result<(), Vec<UnsatisfiedConstraint<F>>> {
+        let unsatisfied = self.unsatisfied.borrow();
+        if unsatisfied.is_empty() {
+            Ok(())
+        } else {
+            Err(unsatisfied.clone())
+        }
+    }
+}
+
+impl<F: Field> Default for TestCS<F> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<F: Field> Clone for TestCS<F> {
@@ -56,12 +156,12 @@ impl<F: Field> ConstraintSystem for TestCS<F> {
     type F = F;
 
     fn alloc(&self, v: Option<Num<Self::F>>) -> Index {
-        let mut naux_ref = self.ninputs.borrow_mut();
+        let mut naux_ref = self.naux.borrow_mut();
         let naux = *naux_ref;
         *naux_ref += 1;
-        let index = Index::Input(naux);
-        if v.is_some() {
-            (*self.variables.borrow_mut()).insert(index, v.unwrap());
+        let index = Index::Aux(naux);
+        if let Some(v) = v {
+            self.variables.borrow_mut().insert(index, v);
         }
         index
     }
@@ -70,19 +170,310 @@ impl<F: Field> ConstraintSystem for TestCS<F> {
         let ninputs = *ninputs_ref;
         *ninputs_ref += 1;
         let index = Index::Input(ninputs);
-        if v.is_some() {
-            (*self.variables.borrow_mut()).insert(index, v.unwrap());
+        if let Some(v) = v {
+            self.variables.borrow_mut().insert(index, v);
+        }
+        index
+    }
+
+    fn enforce(&self, a: &CNum<Self>, b: &CNum<Self>, c: &CNum<Self>) {
+        let mut ncons_ref = self.ncons.borrow_mut();
+        let ncons = *ncons_ref;
+        *ncons_ref += 1;
+        if let (Some(a_value), Some(b_value), Some(c_value)) = (a.value, b.value, c.value) {
+            if a_value * b_value != c_value {
+                let failure = UnsatisfiedConstraint {
+                    index: ncons,
+                    namespace: self.namespace.borrow().join("/"),
+                    a: a_value,
+                    b: b_value,
+                    c: c_value,
+                    constraint: Constraint::new(a, b, c),
+                };
+                if self.collect_unsatisfied {
+                    self.unsatisfied.borrow_mut().push(failure);
+                } else {
+                    panic!("Not satisfied constraint: {}", failure);
+                }
+            }
         }
+    }
+
+    fn push_namespace(&self, name: &str) {
+        self.namespace.borrow_mut().push(name.to_string());
+    }
+
+    fn pop_namespace(&self) {
+        self.namespace.borrow_mut().pop();
+    }
+
+    fn lc_limit(&self) -> Option<usize> {
+        *self.lc_limit.borrow()
+    }
+
+    fn set_lc_limit(&self, limit: Option<usize>) {
+        *self.lc_limit.borrow_mut() = limit;
+    }
+}
+
+pub struct R1CSCS<F: Field> {
+    pub r1cs: RefCell<R1CS<F>>,
+    pub lc_limit: RefCell<Option<usize>>,
+}
+
+impl<F: Field> R1CSCS<F> {
+    pub fn new() -> Self {
+        Self {
+            r1cs: RefCell::new(R1CS::new()),
+            lc_limit: RefCell::new(None),
+        }
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.r1cs.borrow().num_constraints()
+    }
+
+    pub fn into_r1cs(self) -> R1CS<F> {
+        self.r1cs.into_inner()
+    }
+}
+
+impl<F: Field> Default for R1CSCS<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> Clone for R1CSCS<F> {
+    fn clone(&self) -> Self {
+        panic!("Clone is not implemented for R1CSCS")
+    }
+}
+
+impl<F: Field> ConstraintSystem for R1CSCS<F> {
+    type F = F;
+
+    fn alloc(&self, _: Option<Num<Self::F>>) -> Index {
+        let mut r1cs = self.r1cs.borrow_mut();
+        let index = Index::Aux(r1cs.num_aux);
+        r1cs.num_aux += 1;
+        index
+    }
+
+    fn alloc_input(&self, _: Option<Num<Self::F>>) -> Index {
+        let mut r1cs = self.r1cs.borrow_mut();
+        let index = Index::Input(r1cs.num_inputs);
+        r1cs.num_inputs += 1;
         index
     }
 
     fn enforce(&self, a: &CNum<Self>, b: &CNum<Self>, c: &CNum<Self>) {
-        *self.ncons.borrow_mut() += 1;
-        match (a.value, b.value, c.value) {
-            (Some(a), Some(b), Some(c)) => {
+        self.r1cs
+            .borrow_mut()
+            .constraints
+            .push(Constraint::new(a, b, c));
+    }
+
+    fn lc_limit(&self) -> Option<usize> {
+        *self.lc_limit.borrow()
+    }
+
+    fn set_lc_limit(&self, limit: Option<usize>) {
+        *self.lc_limit.borrow_mut() = limit;
+    }
+}
+
+pub struct WitnessCS<F: Field> {
+    pub inputs: RefCell<Vec<Option<Num<F>>>>,
+    pub aux: RefCell<Vec<Option<Num<F>>>>,
+    pub lc_limit: RefCell<Option<usize>>,
+    check_constraints: bool,
+}
+
+impl<F: Field> WitnessCS<F> {
+    pub fn new() -> Self {
+        Self {
+            inputs: RefCell::new(vec![Some(Num::one())]),
+            aux: RefCell::new(vec![]),
+            lc_limit: RefCell::new(None),
+            check_constraints: false,
+        }
+    }
+
+    // assert every constraint with known values, as TestCS does
+    pub fn new_checked() -> Self {
+        Self {
+            check_constraints: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn generate<C: Circuit<F = F>>(c: &C) -> Option<Witness<F>> {
+        let cs = Self::new();
+        c.synthesize(&cs);
+        cs.into_witness()
+    }
+
+    // returns None if some of the values are not assigned
+    pub fn into_witness(self) -> Option<Witness<F>> {
+        Some(Witness {
+            inputs: self
+                .inputs
+                .into_inner()
+                .into_iter()
+                .collect::<Option<_>>()?,
+            aux: self.aux.into_inner().into_iter().collect::<Option<_>>()?,
+        })
+    }
+}
+
+impl<F: Field> Default for WitnessCS<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> Clone for WitnessCS<F> {
+    fn clone(&self) -> Self {
+        panic!("Clone is not implemented for WitnessCS")
+    }
+}
+
+impl<F: Field> ConstraintSystem for WitnessCS<F> {
+    type F = F;
+
+    fn alloc(&self, value: Option<Num<Self::F>>) -> Index {
+        let mut aux = self.aux.borrow_mut();
+        aux.push(value);
+        Index::Aux(aux.len() - 1)
+    }
+
+    fn alloc_input(&self, value: Option<Num<Self::F>>) -> Index {
+        let mut inputs = self.inputs.borrow_mut();
+        inputs.push(value);
+        Index::Input(inputs.len() - 1)
+    }
+
+    fn enforce(&self, a: &CNum<Self>, b: &CNum<Self>, c: &CNum<Self>) {
+        if self.check_constraints {
+            if let (Some(a), Some(b), Some(c)) = (a.value, b.value, c.value) {
                 assert!(a * b == c, "Not satisfied constraint");
             }
-            _ => {}
         }
     }
+
+    fn lc_limit(&self) -> Option<usize> {
+        *self.lc_limit.borrow()
+    }
+
+    fn set_lc_limit(&self, limit: Option<usize>) {
+        *self.lc_limit.borrow_mut() = limit;
+    }
+}
+
+#[cfg(test)]
+mod cs_test {
+    use bellman::pairing::bn256::Fr;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::{circuit::ecc::CEdwardsPoint, core::signal::Signal, native::bn256::JubJubBN256};
+
+    #[test]
+    fn test_check_satisfied() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new_collecting();
+        let a: Num<Fr> = rng.gen();
+        let b: Num<Fr> = rng.gen();
+
+        let signal_a = CNum::alloc(cs, Some(&a));
+        let signal_b = CNum::alloc(cs, Some(&b));
+        (&signal_a * &signal_b).assert_const(&(a * b));
+        assert!(cs.check_satisfied().is_ok());
+
+        {
+            let _ns = cs.namespace("outer");
+            {
+                let _ns = cs.namespace("inner");
+                signal_a.assert_const(&b);
+            }
+            signal_b.assert_const(&a);
+        }
+
+        let unsatisfied = cs.check_satisfied().unwrap_err();
+        assert!(unsatisfied.len() == 2);
+        assert!(unsatisfied[0].index == 2);
+        assert!(unsatisfied[0].namespace == "outer/inner");
+        assert!(unsatisfied[0].a == a && unsatisfied[0].c == b);
+        assert!(unsatisfied[0].constraint.a == vec![(Index::Aux(0), Num::one())]);
+        assert!(unsatisfied[1].index == 3);
+        assert!(unsatisfied[1].namespace == "outer");
+        assert!(cs.namespace.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_alloc_indices() {
+        let cs = &TestCS::<Fr>::new();
+        assert!(cs.alloc(Some(num!(1))) == Index::Aux(0));
+        assert!(cs.alloc_input(Some(num!(2))) == Index::Input(1));
+        assert!(cs.alloc(None) == Index::Aux(1));
+        assert!(cs.alloc_input(None) == Index::Input(2));
+        assert!(*cs.naux.borrow() == 2 && *cs.ninputs.borrow() == 3);
+        assert!(cs.variables.borrow().get(&Index::Aux(0)) == Some(&num!(1)));
+        assert!(cs.variables.borrow().get(&Index::Input(1)) == Some(&num!(2)));
+        assert!(cs.variables.borrow().get(&Index::Aux(1)).is_none());
+    }
+
+    #[test]
+    fn test_gadget_namespace() {
+        let jubjub_params = JubJubBN256::new();
+        let cs = &TestCS::<Fr>::new_collecting();
+        // x = 2 is not an x coordinate of a subgroup point
+        let signal_x = CNum::alloc(cs, Some(&num!(2)));
+        {
+            let _ns = cs.namespace("outer");
+            CEdwardsPoint::subgroup_decompress(&signal_x, &jubjub_params);
+        }
+
+        let unsatisfied = cs.check_satisfied().unwrap_err();
+        assert!(unsatisfied
+            .iter()
+            .all(|u| u.namespace == "outer/subgroup_decompress"));
+    }
+
+    struct Inputs {
+        a: Option<Num<Fr>>,
+        b: Option<Num<Fr>>,
+    }
+
+    impl Circuit for Inputs {
+        type F = Fr;
+
+        fn synthesize<CS: ConstraintSystem<F = Fr>>(&self, cs: &CS) {
+            let a = CNum::alloc(cs, self.a.as_ref());
+            let b = CNum::alloc(cs, self.b.as_ref());
+            (&a * &b).inputize();
+            CNum::from_const(cs, &num!(3)).inputize();
+            a.inputize();
+        }
+    }
+
+    #[test]
+    fn test_get_inputs() {
+        let mut rng = thread_rng();
+        let a: Num<Fr> = rng.gen();
+        let b: Num<Fr> = rng.gen();
+
+        let c = Inputs {
+            a: Some(a),
+            b: Some(b),
+        };
+        assert!(c.get_inputs().unwrap() == vec![a * b, num!(3), a]);
+
+        let c = Inputs {
+            a: Some(a),
+            b: None,
+        };
+        assert!(c.get_inputs().is_none());
+    }
 }
@@ -1,6 +1,12 @@
+pub mod analysis;
+pub mod array;
+pub mod boundedvec;
 pub mod cs;
 pub mod field;
+pub mod optimizer;
 pub mod osrng;
+pub mod profiler;
+pub mod r1cs;
 pub mod seedbox;
 pub mod signal;
 pub mod sizedvec;
@@ -0,0 +1,261 @@
+use std::collections::{HashMap, HashSet};
+
+use borsh::BorshSerialize;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    circuit::num::{lc_merge, Index},
+    core::cs::{Circuit, WitnessCS},
+    core::field::Field,
+    core::r1cs::{Constraint, LinearCombination, Witness, R1CS},
+    native::num::Num,
+};
+
+// the optimized constraint system keeps the inputs, aux[i] is the index of i-th aux variable
+// in the original constraint system
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct Optimized<F: Field> {
+    pub r1cs: R1CS<F>,
+    pub aux: Vec<usize>,
+}
+
+impl<F: Field> Optimized<F> {
+    pub fn remap_witness(&self, witness: &Witness<F>) -> Witness<F> {
+        Witness {
+            inputs: witness.inputs.clone(),
+            aux: self.aux.iter().map(|&i| witness.aux[i]).collect(),
+        }
+    }
+
+    pub fn generate_witness<C: Circuit<F = F>>(&self, c: &C) -> Option<Witness<F>> {
+        WitnessCS::generate(c).map(|w| self.remap_witness(&w))
+    }
+}
+
+fn as_const<F: Field>(lc: &[(Index, Num<F>)]) -> Option<Num<F>> {
+    match lc {
+        [] => Some(Num::zero()),
+        [(Index::Input(0), v)] => Some(*v),
+        _ => None,
+    }
+}
+
+// a * b - c, if a or b is a constant
+fn as_linear<F: Field>(c: &Constraint<F>) -> Option<LinearCombination<F>> {
+    let (k, lc) = match (as_const(&c.a), as_const(&c.b)) {
+        (Some(k), _) => (k, &c.b),
+        (_, Some(k)) => (k, &c.a),
+        _ => return None,
+    };
+    let lc = lc.iter().map(|&(i, v)| (i, v * k)).collect::<Vec<_>>();
+    // a zero constant or zero coefficients of the constraint leave zero terms, they are dropped
+    Some(
+        lc_merge(&lc, &c.c, |v| -v)
+            .into_iter()
+            .filter(|(_, v)| !v.is_zero())
+            .collect(),
+    )
+}
+
+struct Substitutions<F: Field>(HashMap<usize, LinearCombination<F>>);
+
+impl<F: Field> Substitutions<F> {
+    // the resolved linear combinations are stored back, so every substitution is resolved once
+    fn resolve(&mut self, lc: &[(Index, Num<F>)]) -> LinearCombination<F> {
+        let mut res = vec![];
+        let mut rest = vec![];
+        for &(i, v) in lc.iter() {
+            match i {
+                Index::Aux(n) if self.0.contains_key(&n) => rest.push((n, v)),
+                _ => res.push((i, v)),
+            }
+        }
+        for (n, v) in rest {
+            let s = self.0.remove(&n).unwrap();
+            let s = self.resolve(&s);
+            res = lc_merge(&res, &s, |x| x * v);
+            self.0.insert(n, s);
+        }
+        res
+    }
+
+    fn resolve_constraint(&mut self, c: &Constraint<F>) -> Constraint<F> {
+        Constraint {
+            a: self.resolve(&c.a),
+            b: self.resolve(&c.b),
+            c: self.resolve(&c.c),
+        }
+    }
+}
+
+// removes the linear constraints by the substitution of aux variables, the duplicated constraints
+// and the aux variables, which are not used in any constraint
+pub fn optimize<F: Field>(r1cs: &R1CS<F>) -> Optimized<F> {
+    let mut subst = Substitutions(HashMap::new());
+    let mut constraints = r1cs.constraints.clone();
+
+    // the substitution could turn the constraints met before into linear ones, so the
+    // passes are repeated until there is nothing to substitute
+    loop {
+        let nsubst = subst.0.len();
+        let mut res = vec![];
+        for c in constraints.iter() {
+            let c = subst.resolve_constraint(c);
+            match as_linear(&c) {
+                Some(lc) => {
+                    if lc.is_empty() {
+                        continue;
+                    }
+                    // the newest variable is eliminated, as it is usually defined by the constraint
+                    match lc.iter().rev().find(|(i, _)| matches!(i, Index::Aux(_))) {
+                        Some(&(Index::Aux(n), k)) => {
+                            let k = -k.inverse();
+                            let s = lc
+                                .iter()
+                                .filter(|&&(i, _)| i != Index::Aux(n))
+                                .map(|&(i, v)| (i, v * k))
+                                .collect();
+                            subst.0.insert(n, s);
+                        }
+                        _ => res.push(c),
+                    }
+                }
+                None => res.push(c),
+            }
+        }
+        constraints = res;
+        if subst.0.len() == nsubst {
+            break;
+        }
+    }
+
+    let mut seen = HashSet::new();
+    constraints.retain(|c| {
+        let (a, b) = (c.a.try_to_vec().unwrap(), c.b.try_to_vec().unwrap());
+        let key = (
+            a.clone().min(b.clone()),
+            a.max(b),
+            c.c.try_to_vec().unwrap(),
+        );
+        seen.insert(key)
+    });
+
+    let mut used = vec![false; r1cs.num_aux];
+    for c in constraints.iter() {
+        for &(i, _) in c.a.iter().chain(c.b.iter()).chain(c.c.iter()) {
+            if let Index::Aux(n) = i {
+                used[n] = true;
+            }
+        }
+    }
+    let aux = (0..r1cs.num_aux).filter(|&n| used[n]).collect::<Vec<_>>();
+    let mut remap = vec![0; r1cs.num_aux];
+    for (new, &old) in aux.iter().enumerate() {
+        remap[old] = new;
+    }
+
+    let remap_lc = |lc: &LinearCombination<F>| {
+        lc.iter()
+            .map(|&(i, v)| match i {
+                Index::Aux(n) => (Index::Aux(remap[n]), v),
+                i => (i, v),
+            })
+            .collect()
+    };
+
+    Optimized {
+        r1cs: R1CS {
+            num_inputs: r1cs.num_inputs,
+            num_aux: aux.len(),
+            constraints: constraints
+                .iter()
+                .map(|c| Constraint {
+                    a: remap_lc(&c.a),
+                    b: remap_lc(&c.b),
+                    c: remap_lc(&c.c),
+                })
+                .collect(),
+        },
+        aux,
+    }
+}
+
+#[cfg(test)]
+mod optimizer_test {
+    use bellman::pairing::bn256::Fr;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::{
+        circuit::num::CNum, circuit::poseidon::c_poseidon, core::cs::ConstraintSystem,
+        core::signal::Signal, native::poseidon::PoseidonParams,
+    };
+
+    #[derive(Default)]
+    struct CheckPreimageKnowledge<F: Field> {
+        image: Option<Num<F>>,
+        preimage: Option<Num<F>>,
+    }
+
+    impl<F: Field> Circuit for CheckPreimageKnowledge<F> {
+        type F = F;
+        fn synthesize<CS: ConstraintSystem<F = F>>(&self, cs: &CS) {
+            cs.set_lc_limit(Some(2));
+            let image = CNum::alloc(cs, self.image.as_ref());
+            image.inputize();
+            let preimage = CNum::alloc(cs, self.preimage.as_ref());
+            preimage.assert_nonzero();
+            preimage.assert_nonzero();
+            let bit = CNum::alloc(cs, Some(&Num::one()));
+            bit.assert_bit();
+            bit.assert_bit();
+            let poseidon_params = &PoseidonParams::<F>::new(3, 8, 53);
+            let image_computed = c_poseidon(&[preimage.clone(), preimage], poseidon_params);
+            (&image - &image_computed).is_zero().assert_true();
+        }
+    }
+
+    #[test]
+    fn test_optimize() {
+        let mut rng = thread_rng();
+        let preimage = rng.gen();
+        let poseidon_params = &PoseidonParams::<Fr>::new(3, 8, 53);
+        let image = crate::native::poseidon::poseidon(&[preimage, preimage], poseidon_params);
+        let c = CheckPreimageKnowledge::<Fr> {
+            image: Some(image),
+            preimage: Some(preimage),
+        };
+
+        let r1cs = R1CS::from_circuit(&c);
+        let optimized = optimize(&r1cs);
+        println!(
+            "constraints: {} -> {}, aux: {} -> {}",
+            r1cs.num_constraints(),
+            optimized.r1cs.num_constraints(),
+            r1cs.num_aux,
+            optimized.r1cs.num_aux
+        );
+        assert!(optimized.r1cs.num_constraints() < r1cs.num_constraints());
+        assert!(optimized.r1cs.num_aux < r1cs.num_aux);
+        assert!(optimized.r1cs.num_inputs == r1cs.num_inputs);
+        assert!(optimized
+            .r1cs
+            .constraints
+            .iter()
+            .all(|c| as_linear(c).is_none()));
+        let constraints = &optimized.r1cs.constraints;
+        assert!((1..constraints.len()).all(|i| !constraints[..i].contains(&constraints[i])));
+
+        let witness = optimized.generate_witness(&c).unwrap();
+        assert!(witness.aux.len() == optimized.r1cs.num_aux);
+        assert!(optimized.r1cs.is_satisfied(&witness));
+
+        let c = CheckPreimageKnowledge::<Fr> {
+            image: Some(image + Num::one()),
+            preimage: Some(preimage),
+        };
+        let witness = optimized.generate_witness(&c).unwrap();
+        assert!(!optimized.r1cs.is_satisfied(&witness));
+    }
+}
@@ -0,0 +1,306 @@
+use std::{cell::RefCell, fmt::Write};
+
+use crate::{
+    circuit::num::{CNum, Index},
+    core::cs::{Circuit, ConstraintSystem},
+    core::field::Field,
+    native::num::Num,
+};
+
+// counters are exclusive, use total_* methods to include the children
+#[derive(Clone, Debug)]
+pub struct ProfileNode {
+    pub name: String,
+    pub calls: usize,
+    pub constraints: usize,
+    pub aux: usize,
+    pub inputs: usize,
+    pub children: Vec<ProfileNode>,
+}
+
+impl ProfileNode {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            calls: 0,
+            constraints: 0,
+            aux: 0,
+            inputs: 0,
+            children: vec![],
+        }
+    }
+
+    pub fn total_constraints(&self) -> usize {
+        self.constraints
+            + self
+                .children
+                .iter()
+                .map(|c| c.total_constraints())
+                .sum::<usize>()
+    }
+
+    pub fn total_aux(&self) -> usize {
+        self.aux + self.children.iter().map(|c| c.total_aux()).sum::<usize>()
+    }
+
+    pub fn total_inputs(&self) -> usize {
+        self.inputs
+            + self
+                .children
+                .iter()
+                .map(|c| c.total_inputs())
+                .sum::<usize>()
+    }
+
+    // path is relative to this node, e.g. "eddsaposeidon_verify/poseidon"
+    pub fn get(&self, path: &str) -> Option<&ProfileNode> {
+        path.split('/').try_fold(self, |node, name| {
+            node.children.iter().find(|c| c.name == name)
+        })
+    }
+
+    // markdown table with inclusive counters, constraints per call are averaged over all calls
+    pub fn table(&self) -> String {
+        fn rows(node: &ProfileNode, depth: usize, res: &mut String) {
+            let total = node.total_constraints();
+            writeln!(
+                res,
+                "| {}{} | {} | {} | {} | {} | {} |",
+                "&nbsp;&nbsp;".repeat(depth),
+                node.name,
+                node.calls,
+                total,
+                total / node.calls.max(1),
+                node.total_aux(),
+                node.total_inputs()
+            )
+            .unwrap();
+            for c in node.children.iter() {
+                rows(c, depth + 1, res);
+            }
+        }
+
+        let mut res = String::from(
+            "| Gadget | Calls | Constraints | Per call | Aux | Inputs |\n| - | - | - | - | - | - |\n",
+        );
+        rows(self, 0, &mut res);
+        res
+    }
+
+    // folded stacks with exclusive constraint counts, compatible with flamegraph.pl and inferno
+    pub fn folded(&self) -> String {
+        fn lines(node: &ProfileNode, prefix: &str, res: &mut String) {
+            let path = if prefix.is_empty() {
+                node.name.clone()
+            } else {
+                format!("{};{}", prefix, node.name)
+            };
+            if node.constraints > 0 {
+                writeln!(res, "{} {}", path, node.constraints).unwrap();
+            }
+            for c in node.children.iter() {
+                lines(c, &path, res);
+            }
+        }
+
+        let mut res = String::new();
+        lines(self, "", &mut res);
+        res
+    }
+}
+
+pub struct ProfilerCS<F: Field> {
+    pub ninputs: RefCell<usize>,
+    pub naux: RefCell<usize>,
+    pub lc_limit: RefCell<Option<usize>>,
+    root: RefCell<ProfileNode>,
+    stack: RefCell<Vec<usize>>,
+    f: std::marker::PhantomData<F>,
+}
+
+impl<F: Field> ProfilerCS<F> {
+    pub fn new() -> Self {
+        let mut root = ProfileNode::new("root");
+        root.calls = 1;
+        Self {
+            ninputs: RefCell::new(1),
+            naux: RefCell::new(0),
+            lc_limit: RefCell::new(None),
+            root: RefCell::new(root),
+            stack: RefCell::new(vec![]),
+            f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn profile<C: Circuit<F = F>>(c: &C) -> ProfileNode {
+        let cs = Self::new();
+        c.synthesize(&cs);
+        cs.into_profile()
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.root.borrow().total_constraints()
+    }
+
+    pub fn into_profile(self) -> ProfileNode {
+        self.root.into_inner()
+    }
+
+    fn with_current<R>(&self, f: impl FnOnce(&mut ProfileNode) -> R) -> R {
+        let mut root = self.root.borrow_mut();
+        let node = self
+            .stack
+            .borrow()
+            .iter()
+            .fold(&mut *root, |node, &i| &mut node.children[i]);
+        f(node)
+    }
+}
+
+impl<F: Field> Default for ProfilerCS<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> Clone for ProfilerCS<F> {
+    fn clone(&self) -> Self {
+        panic!("Clone is not implemented for ProfilerCS")
+    }
+}
+
+impl<F: Field> ConstraintSystem for ProfilerCS<F> {
+    type F = F;
+
+    fn alloc(&self, _: Option<Num<Self::F>>) -> Index {
+        let mut naux_ref = self.naux.borrow_mut();
+        let naux = *naux_ref;
+        *naux_ref += 1;
+        self.with_current(|node| node.aux += 1);
+        Index::Aux(naux)
+    }
+
+    fn alloc_input(&self, _: Option<Num<Self::F>>) -> Index {
+        let mut ninputs_ref = self.ninputs.borrow_mut();
+        let ninputs = *ninputs_ref;
+        *ninputs_ref += 1;
+        self.with_current(|node| node.inputs += 1);
+        Index::Input(ninputs)
+    }
+
+    fn enforce(&self, _: &CNum<Self>, _: &CNum<Self>, _: &CNum<Self>) {
+        self.with_current(|node| node.constraints += 1);
+    }
+
+    fn push_namespace(&self, name: &str) {
+        let i = self.with_current(|node| {
+            let i = match node.children.iter().position(|c| c.name == name) {
+                Some(i) => i,
+                None => {
+                    node.children.push(ProfileNode::new(name));
+                    node.children.len() - 1
+                }
+            };
+            node.children[i].calls += 1;
+            i
+        });
+        self.stack.borrow_mut().push(i);
+    }
+
+    fn pop_namespace(&self) {
+        self.stack.borrow_mut().pop();
+    }
+
+    fn lc_limit(&self) -> Option<usize> {
+        *self.lc_limit.borrow()
+    }
+
+    fn set_lc_limit(&self, limit: Option<usize>) {
+        *self.lc_limit.borrow_mut() = limit;
+    }
+}
+
+#[cfg(test)]
+mod profiler_test {
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::{
+        circuit::eddsaposeidon::c_eddsaposeidon_verify,
+        core::cs::TestCS,
+        core::signal::Signal,
+        native::bn256::{Fr, JubJubBN256},
+        native::ecc::JubJubParams,
+        native::eddsaposeidon::eddsaposeidon_sign,
+        native::poseidon::PoseidonParams,
+    };
+
+    #[derive(Default)]
+    struct EddsaPoseidon {
+        s: Option<Num<Fr>>,
+        r: Option<Num<Fr>>,
+        a: Option<Num<Fr>>,
+        m: Option<Num<Fr>>,
+    }
+
+    impl Circuit for EddsaPoseidon {
+        type F = Fr;
+
+        fn synthesize<CS: ConstraintSystem<F = Fr>>(&self, cs: &CS) {
+            let poseidon_params = PoseidonParams::<Fr>::new(4, 8, 54);
+            let jubjub_params = JubJubBN256::new();
+            let s = CNum::alloc(cs, self.s.as_ref());
+            let r = CNum::alloc(cs, self.r.as_ref());
+            let a = CNum::alloc(cs, self.a.as_ref());
+            let m = CNum::alloc(cs, self.m.as_ref());
+            m.inputize();
+            c_eddsaposeidon_verify(&s, &r, &a, &m, &poseidon_params, &jubjub_params).assert_true();
+        }
+    }
+
+    #[test]
+    fn test_profile_eddsaposeidon() {
+        let mut rng = thread_rng();
+        let poseidon_params = PoseidonParams::<Fr>::new(4, 8, 54);
+        let jubjub_params = JubJubBN256::new();
+        let sk = rng.gen();
+        let m = rng.gen();
+        let (s, r) = eddsaposeidon_sign(sk, m, &poseidon_params, &jubjub_params);
+        let a = jubjub_params.edwards_g().mul(sk, &jubjub_params).x;
+        let c = EddsaPoseidon {
+            s: Some(s.into_other()),
+            r: Some(r),
+            a: Some(a),
+            m: Some(m),
+        };
+
+        let cs = &TestCS::<Fr>::new();
+        c.synthesize(cs);
+
+        let profile = ProfilerCS::profile(&c);
+        assert!(profile.total_constraints() == cs.num_constraints());
+        assert!(profile.total_aux() == *cs.naux.borrow());
+        assert!(profile.total_inputs() == 1);
+
+        let verify = profile.get("eddsaposeidon_verify").unwrap();
+        assert!(verify.calls == 1);
+        assert!(verify.get("subgroup_decompress").unwrap().calls == 2);
+        assert!(verify.get("poseidon").unwrap().total_constraints() > 0);
+        assert!(verify.get("edwards_mul_const").is_some());
+        assert!(verify.get("edwards_mul").is_some());
+
+        let table = profile.table();
+        assert!(table.lines().count() > 2);
+        println!("{}", table);
+
+        let folded = profile.folded();
+        let folded_total = folded
+            .lines()
+            .map(|l| l.rsplit(' ').next().unwrap().parse::<usize>().unwrap())
+            .sum::<usize>();
+        assert!(folded_total == cs.num_constraints());
+        assert!(folded
+            .lines()
+            .any(|l| l.starts_with("root;eddsaposeidon_verify;poseidon ")));
+    }
+}
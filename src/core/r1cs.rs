@@ -0,0 +1,423 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+};
+
+use blake2_rfc::blake2s::Blake2s;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    circuit::num::{CNum, Index},
+    core::cs::{Circuit, ConstraintSystem, R1CSCS},
+    core::field::Field,
+    native::num::Num,
+};
+
+pub type LinearCombination<F> = Vec<(Index, Num<F>)>;
+
+// a * b = c, every linear combination is sorted by Index
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct Constraint<F: Field> {
+    pub a: LinearCombination<F>,
+    pub b: LinearCombination<F>,
+    pub c: LinearCombination<F>,
+}
+
+// num_inputs includes the constant one at Index::Input(0)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct R1CS<F: Field> {
+    pub num_inputs: usize,
+    pub num_aux: usize,
+    pub constraints: Vec<Constraint<F>>,
+}
+
+impl<F: Field> Constraint<F> {
+    pub fn new<CS: ConstraintSystem<F = F>>(a: &CNum<CS>, b: &CNum<CS>, c: &CNum<CS>) -> Self {
+        Self {
+            a: a.lc.clone(),
+            b: b.lc.clone(),
+            c: c.lc.clone(),
+        }
+    }
+}
+
+impl<F: Field> R1CS<F> {
+    pub fn new() -> Self {
+        Self {
+            num_inputs: 1,
+            num_aux: 0,
+            constraints: vec![],
+        }
+    }
+
+    pub fn from_circuit<C: Circuit<F = F>>(c: &C) -> Self {
+        let cs = R1CSCS::new();
+        c.synthesize(&cs);
+        cs.into_r1cs()
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    // returns indexes of the constraints not satisfied by the witness
+    pub fn unsatisfied(&self, witness: &Witness<F>) -> Vec<usize> {
+        assert!(
+            witness.inputs.len() == self.num_inputs && witness.aux.len() == self.num_aux,
+            "witness should match the constraint system"
+        );
+        self.constraints
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| witness.eval(&c.a) * witness.eval(&c.b) != witness.eval(&c.c))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn is_satisfied(&self, witness: &Witness<F>) -> bool {
+        self.unsatisfied(witness).is_empty()
+    }
+
+    // equal to CircuitDigest::from_circuit of the circuit
+    pub fn digest(&self) -> CircuitDigest {
+        let mut h = CircuitDigest::hasher();
+        for c in self.constraints.iter() {
+            BorshSerialize::serialize(c, &mut h).unwrap();
+        }
+        CircuitDigest::finalize(h, self.num_inputs, self.num_aux, self.num_constraints())
+    }
+}
+
+impl<F: Field> Default for R1CS<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// hash of the constraints, it does not depend on the witness
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CircuitDigest(pub [u8; 32]);
+
+impl CircuitDigest {
+    // the constraints are hashed during the synthesis, the R1CS is not stored
+    pub fn from_circuit<C: Circuit>(c: &C) -> Self {
+        let cs = DigestCS::new();
+        c.synthesize(&cs);
+        cs.into_digest()
+    }
+
+    fn hasher() -> Blake2s {
+        Blake2s::with_params(32, &[], &[], b"fwk_r1cs")
+    }
+
+    // the sizes go after the constraints, so the constraints are hashed as they are enforced
+    fn finalize(mut h: Blake2s, num_inputs: usize, num_aux: usize, num_constraints: usize) -> Self {
+        for n in [num_inputs, num_aux, num_constraints].iter() {
+            BorshSerialize::serialize(&(*n as u64), &mut h).unwrap();
+        }
+        let mut res = [0u8; 32];
+        res.clone_from_slice(h.finalize().as_ref());
+        CircuitDigest(res)
+    }
+}
+
+// hashes every constraint as it is enforced, the memory does not grow with the circuit
+pub struct DigestCS<F: Field> {
+    hasher: RefCell<Blake2s>,
+    num_inputs: RefCell<usize>,
+    num_aux: RefCell<usize>,
+    num_constraints: RefCell<usize>,
+    lc_limit: RefCell<Option<usize>>,
+    f: std::marker::PhantomData<F>,
+}
+
+impl<F: Field> DigestCS<F> {
+    pub fn new() -> Self {
+        Self {
+            hasher: RefCell::new(CircuitDigest::hasher()),
+            num_inputs: RefCell::new(1),
+            num_aux: RefCell::new(0),
+            num_constraints: RefCell::new(0),
+            lc_limit: RefCell::new(None),
+            f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn into_digest(self) -> CircuitDigest {
+        CircuitDigest::finalize(
+            self.hasher.into_inner(),
+            self.num_inputs.into_inner(),
+            self.num_aux.into_inner(),
+            self.num_constraints.into_inner(),
+        )
+    }
+}
+
+impl<F: Field> Default for DigestCS<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> Clone for DigestCS<F> {
+    fn clone(&self) -> Self {
+        panic!("Clone is not implemented for DigestCS")
+    }
+}
+
+impl<F: Field> ConstraintSystem for DigestCS<F> {
+    type F = F;
+
+    fn alloc(&self, _: Option<Num<Self::F>>) -> Index {
+        let mut num_aux = self.num_aux.borrow_mut();
+        *num_aux += 1;
+        Index::Aux(*num_aux - 1)
+    }
+
+    fn alloc_input(&self, _: Option<Num<Self::F>>) -> Index {
+        let mut num_inputs = self.num_inputs.borrow_mut();
+        *num_inputs += 1;
+        Index::Input(*num_inputs - 1)
+    }
+
+    // the same bytes as the borsh encoding of Constraint
+    fn enforce(&self, a: &CNum<Self>, b: &CNum<Self>, c: &CNum<Self>) {
+        let mut h = self.hasher.borrow_mut();
+        for lc in [&a.lc, &b.lc, &c.lc].iter() {
+            BorshSerialize::serialize(*lc, &mut *h).unwrap();
+        }
+        *self.num_constraints.borrow_mut() += 1;
+    }
+
+    fn lc_limit(&self) -> Option<usize> {
+        *self.lc_limit.borrow()
+    }
+
+    fn set_lc_limit(&self, limit: Option<usize>) {
+        *self.lc_limit.borrow_mut() = limit;
+    }
+}
+
+impl fmt::Display for CircuitDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0.iter() {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CircuitDigest {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 64 || !s.is_ascii() {
+            return Err("Wrong digest length");
+        }
+        let mut res = [0u8; 32];
+        for (i, b) in res.iter_mut().enumerate() {
+            *b = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).map_err(|_| "Wrong hex digit")?;
+        }
+        Ok(CircuitDigest(res))
+    }
+}
+
+impl Serialize for CircuitDigest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for CircuitDigest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <String as Deserialize>::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+// assignment in bellman variable order, inputs[0] is the constant one
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct Witness<F: Field> {
+    pub inputs: Vec<Num<F>>,
+    pub aux: Vec<Num<F>>,
+}
+
+impl<F: Field> Witness<F> {
+    pub fn get(&self, index: Index) -> Num<F> {
+        match index {
+            Index::Input(i) => self.inputs[i],
+            Index::Aux(i) => self.aux[i],
+        }
+    }
+
+    pub fn eval(&self, lc: &[(Index, Num<F>)]) -> Num<F> {
+        lc.iter()
+            .fold(Num::zero(), |acc, (k, v)| acc + self.get(*k) * v)
+    }
+}
+
+impl<F: Field> BorshSerialize for Witness<F> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        BorshSerialize::serialize(&self.inputs, writer)?;
+        BorshSerialize::serialize(&self.aux, writer)
+    }
+}
+
+impl<F: Field> BorshDeserialize for Witness<F> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, io::Error> {
+        Ok(Self {
+            inputs: BorshDeserialize::deserialize(buf)?,
+            aux: BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
+impl<F: Field> BorshSerialize for Constraint<F> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        BorshSerialize::serialize(&self.a, writer)?;
+        BorshSerialize::serialize(&self.b, writer)?;
+        BorshSerialize::serialize(&self.c, writer)
+    }
+}
+
+impl<F: Field> BorshDeserialize for Constraint<F> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, io::Error> {
+        Ok(Self {
+            a: BorshDeserialize::deserialize(buf)?,
+            b: BorshDeserialize::deserialize(buf)?,
+            c: BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
+impl<F: Field> BorshSerialize for R1CS<F> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        BorshSerialize::serialize(&(self.num_inputs as u64), writer)?;
+        BorshSerialize::serialize(&(self.num_aux as u64), writer)?;
+        BorshSerialize::serialize(&self.constraints, writer)
+    }
+}
+
+impl<F: Field> BorshDeserialize for R1CS<F> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, io::Error> {
+        Ok(Self {
+            num_inputs: <u64 as BorshDeserialize>::deserialize(buf)? as usize,
+            num_aux: <u64 as BorshDeserialize>::deserialize(buf)? as usize,
+            constraints: BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod r1cs_test {
+    use bellman::pairing::bn256::Fr;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::{
+        circuit::poseidon::c_poseidon, core::cs::WitnessCS, core::signal::Signal,
+        native::poseidon::PoseidonParams,
+    };
+
+    #[derive(Default)]
+    struct CheckPreimageKnowledge<F: Field> {
+        image: Option<Num<F>>,
+        preimage: Option<Num<F>>,
+    }
+
+    impl<F: Field> Circuit for CheckPreimageKnowledge<F> {
+        type F = F;
+        fn synthesize<CS: ConstraintSystem<F = F>>(&self, cs: &CS) {
+            let image = CNum::alloc(cs, self.image.as_ref());
+            image.inputize();
+            let preimage = CNum::alloc(cs, self.preimage.as_ref());
+            let poseidon_params = &PoseidonParams::<F>::new(2, 8, 53);
+            let image_computed = c_poseidon([preimage].as_ref(), poseidon_params);
+            (&image - &image_computed).assert_zero();
+        }
+    }
+
+    #[test]
+    fn test_r1cs_export() {
+        let mut rng = thread_rng();
+        let c = CheckPreimageKnowledge::<Fr> {
+            image: Some(rng.gen()),
+            preimage: Some(rng.gen()),
+        };
+
+        let r1cs = R1CS::from_circuit(&c);
+        assert!(r1cs.num_inputs == 2);
+        assert!(r1cs.num_constraints() > 0);
+        assert!(r1cs.constraints.iter().all(|c| {
+            [&c.a, &c.b, &c.c]
+                .iter()
+                .all(|lc| lc.windows(2).all(|w| w[0].0 < w[1].0))
+        }));
+
+        // the shape of the circuit should not depend on the witness
+        let r1cs_default = R1CS::from_circuit(&CheckPreimageKnowledge::<Fr>::default());
+        assert!(r1cs == r1cs_default);
+
+        let bin = r1cs.try_to_vec().unwrap();
+        assert!(R1CS::<Fr>::try_from_slice(&bin).unwrap() == r1cs);
+
+        let json = serde_json::to_string(&r1cs).unwrap();
+        assert!(serde_json::from_str::<R1CS<Fr>>(&json).unwrap() == r1cs);
+    }
+
+    #[test]
+    fn test_digest() {
+        let mut rng = thread_rng();
+        let c = CheckPreimageKnowledge::<Fr> {
+            image: Some(rng.gen()),
+            preimage: Some(rng.gen()),
+        };
+        let digest = CircuitDigest::from_circuit(&c);
+        assert!(digest == CircuitDigest::from_circuit(&CheckPreimageKnowledge::<Fr>::default()));
+
+        let mut r1cs = R1CS::from_circuit(&c);
+        assert!(r1cs.digest() == digest);
+        r1cs.constraints.pop();
+        assert!(r1cs.digest() != digest);
+        r1cs = R1CS::from_circuit(&c);
+        r1cs.num_aux += 1;
+        assert!(r1cs.digest() != digest);
+
+        let json = serde_json::to_string(&digest).unwrap();
+        assert!(json.len() == 66);
+        assert!(serde_json::from_str::<CircuitDigest>(&json).unwrap() == digest);
+    }
+
+    #[test]
+    fn test_witness() {
+        let mut rng = thread_rng();
+        let preimage = rng.gen();
+        let poseidon_params = &PoseidonParams::<Fr>::new(2, 8, 53);
+        let image = crate::native::poseidon::poseidon(&[preimage], poseidon_params);
+        let c = CheckPreimageKnowledge::<Fr> {
+            image: Some(image),
+            preimage: Some(preimage),
+        };
+
+        let r1cs = R1CS::from_circuit(&c);
+        let mut witness = WitnessCS::generate(&c).unwrap();
+        assert!(witness.inputs == vec![Num::one(), image]);
+        assert!(witness.aux[0] == image && witness.aux[1] == preimage);
+        assert!(r1cs.is_satisfied(&witness));
+
+        let bin = witness.try_to_vec().unwrap();
+        assert!(Witness::<Fr>::try_from_slice(&bin).unwrap() == witness);
+
+        witness.aux[1] += Num::one();
+        assert!(!r1cs.is_satisfied(&witness));
+
+        assert!(WitnessCS::generate(&CheckPreimageKnowledge::<Fr>::default()).is_none());
+    }
+}
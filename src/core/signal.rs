@@ -164,3 +164,220 @@ impl<'a, CS: 'a + ConstraintSystem> Signal<'a, CS> for Tuple {
         for_tuples!( #( self.Tuple.linearize_builder(acc); )* );
     }
 }
+
+#[cfg(test)]
+mod signal_test {
+    use bellman::pairing::bn256::Fr;
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use fawkes_crypto_derive::Signal;
+    use rand::{thread_rng, Rng};
+    use typenum::U3;
+
+    use super::*;
+    use crate::{
+        circuit::ecc::CEdwardsPoint,
+        core::cs::{Circuit, TestCS},
+        core::field::Field,
+        core::r1cs::R1CS,
+        native::ecc::EdwardsPoint,
+        native::num::Num,
+        native::poseidon::PoseidonParams,
+    };
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Op<F: Field> {
+        Neg(Num<F>),
+        Add((Num<F>, Num<F>)),
+        Nop(bool),
+    }
+
+    #[derive(Clone, Signal)]
+    #[Value = "Op<CS::F>"]
+    struct COp<'a, CS: ConstraintSystem> {
+        #[signal(selector)]
+        selector: CNum<'a, CS>,
+        #[signal(variant = "Neg")]
+        neg: CNum<'a, CS>,
+        #[signal(variant = "Add")]
+        add: (CNum<'a, CS>, CNum<'a, CS>),
+        #[signal(variant = "Nop")]
+        nop: CBool<'a, CS>,
+    }
+
+    #[test]
+    fn test_signal_enum() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new_collecting();
+        let a: Num<Fr> = rng.gen();
+        let b: Num<Fr> = rng.gen();
+
+        let neg = COp::alloc(cs, Some(&Op::Neg(a)));
+        let add = COp::alloc(cs, Some(&Op::Add((a, b))));
+        let nop = COp::from_const(cs, &Op::Nop(true));
+        assert!(neg.get_value() == Some(Op::Neg(a)));
+        assert!(add.get_value() == Some(Op::Add((a, b))));
+        assert!(add.add.get_value() == Some((a, b)));
+        assert!(nop.as_const() == Some(Op::Nop(true)));
+        assert!(neg.linearize().len() == 5);
+
+        let bit = CBool::alloc(cs, Some(&true));
+        let res = neg.switch(&bit, &add);
+        assert!(res.get_value() == Some(Op::Neg(a)));
+        assert!(res.is_eq(&neg).get_value() == Some(true));
+        assert!(res.is_eq(&add).get_value() == Some(false));
+
+        // the payloads of the inactive variants do not matter
+        let other_neg = COp {
+            add: add.add.clone(),
+            ..neg.clone()
+        };
+        assert!(other_neg.is_eq(&neg).get_value() == Some(true));
+        other_neg.assert_eq(&neg);
+        nop.assert_const(&Op::Nop(true));
+        assert!(cs.check_satisfied().is_ok());
+
+        let wrong = COp {
+            selector: CNum::alloc(cs, Some(&num!(3))),
+            ..neg.clone()
+        };
+        assert!(wrong.get_value().is_none());
+    }
+
+    #[derive(Clone, Signal)]
+    #[signal(generate_value = "Note")]
+    struct CNote<'a, CS: ConstraintSystem, L: Unsigned> {
+        amount: CNum<'a, CS>,
+        path: SizedVec<CBool<'a, CS>, L>,
+        key: CNum<'a, CS>,
+    }
+
+    #[test]
+    fn test_signal_generate_value() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let note = Note::<Fr, U3> {
+            amount: rng.gen(),
+            path: (0..3).map(|_| rng.gen()).collect(),
+            key: rng.gen(),
+        };
+
+        let signal = CNote::alloc(cs, Some(&note));
+        let value = signal.get_value().unwrap();
+        assert!(value.amount == note.amount);
+        assert!(value.path.0 == note.path.0);
+        assert!(value.key == note.key);
+        assert!(signal.linearize().len() == 5);
+
+        let json = serde_json::to_string(&note).unwrap();
+        let note_json = serde_json::from_str::<Note<Fr, U3>>(&json).unwrap();
+        assert!(note_json.amount == note.amount && note_json.path.0 == note.path.0);
+
+        let bin = note.try_to_vec().unwrap();
+        let note_bin = Note::<Fr, U3>::try_from_slice(&bin).unwrap();
+        assert!(note_bin.key == note.key && note_bin.path.0 == note.path.0);
+    }
+
+    #[derive(Clone, Signal)]
+    #[signal(generate_value = "Account")]
+    struct CAccount<'a, CS: ConstraintSystem, L: Unsigned> {
+        #[signal(value = "Note<F, L>")]
+        note: CNote<'a, CS, L>,
+        #[signal(value = "EdwardsPoint<F>")]
+        owner: CEdwardsPoint<'a, CS>,
+    }
+
+    #[test]
+    fn test_signal_generate_value_nested() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let account = Account::<Fr, U3> {
+            note: Note {
+                amount: rng.gen(),
+                path: (0..3).map(|_| rng.gen()).collect(),
+                key: rng.gen(),
+            },
+            owner: EdwardsPoint {
+                x: rng.gen(),
+                y: rng.gen(),
+            },
+        };
+
+        let signal = CAccount::alloc(cs, Some(&account));
+        let value = signal.get_value().unwrap();
+        assert!(value.note.amount == account.note.amount);
+        assert!(value.owner == account.owner);
+        assert!(signal.linearize().len() == 7);
+
+        let json = serde_json::to_string(&account).unwrap();
+        let account_json = serde_json::from_str::<Account<Fr, U3>>(&json).unwrap();
+        assert!(account_json.note.key == account.note.key && account_json.owner == account.owner);
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Leaf<F: Field>(Num<F>, u32);
+
+    #[derive(Clone, Signal)]
+    #[Value = "Leaf<CS::F>"]
+    struct CLeaf<'a, CS: ConstraintSystem>(
+        CNum<'a, CS>,
+        #[signal(skip)] Option<&'a PoseidonParams<CS::F>>,
+        #[signal(constant)] u32,
+    );
+
+    #[test]
+    fn test_signal_tuple() {
+        let mut rng = thread_rng();
+        let cs = &TestCS::<Fr>::new();
+        let a: Num<Fr> = rng.gen();
+        let b: Num<Fr> = rng.gen();
+
+        let leaf = CLeaf::alloc(cs, Some(&Leaf(a, 7)));
+        assert!(leaf.get_value() == Some(Leaf(a, 7)));
+        assert!(leaf.1.is_none() && leaf.2 == 7);
+        assert!(leaf.linearize().len() == 1);
+
+        let other = CLeaf::from_const(cs, &Leaf(b, 7));
+        let bit = CBool::alloc(cs, Some(&false));
+        let res = leaf.switch(&bit, &other);
+        assert!(res.get_value() == Some(Leaf(b, 7)));
+        assert!(res.is_eq(&other).get_value() == Some(true));
+        res.assert_eq(&other);
+        assert!(cs.check_satisfied().is_ok());
+    }
+
+    // the constants are fixed by the circuit, so they are not compared in the circuit
+    #[test]
+    #[should_panic(expected = "constant fields should be equal")]
+    fn test_signal_constant_is_eq() {
+        let cs = &TestCS::<Fr>::new();
+        let leaf = CLeaf::alloc(cs, Some(&Leaf(num!(3), 7)));
+        let wrong = CLeaf::from_const(cs, &Leaf(num!(3), 8));
+        leaf.is_eq(&wrong);
+    }
+
+    struct CompareLeaves {
+        leaf: Option<Leaf<Fr>>,
+    }
+
+    impl Circuit for CompareLeaves {
+        type F = Fr;
+
+        fn synthesize<CS: ConstraintSystem<F = Fr>>(&self, cs: &CS) {
+            let a = CLeaf::alloc(cs, self.leaf.as_ref());
+            let b = CLeaf::alloc(cs, self.leaf.as_ref());
+            a.is_eq(&b).assert();
+        }
+    }
+
+    // the constant fields are the defaults during the setup, the circuit does not depend on them
+    // as long as the compared signals have the same constants
+    #[test]
+    fn test_signal_constant_shape() {
+        let setup = R1CS::from_circuit(&CompareLeaves { leaf: None });
+        let prove = R1CS::from_circuit(&CompareLeaves {
+            leaf: Some(Leaf(num!(3), 7)),
+        });
+        assert!(setup == prove);
+        assert!(setup.num_constraints() > 0);
+    }
+}
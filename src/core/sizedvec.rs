@@ -1,4 +1,6 @@
 use std::{
+    convert::TryFrom,
+    io::{self, Write},
     iter::*,
     marker::PhantomData,
     ops::{Index, IndexMut},
@@ -6,8 +8,9 @@ use std::{
     slice::{Iter, IterMut},
 };
 
+use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{
-    de::{Deserialize, Deserializer},
+    de::{self, Deserialize, Deserializer},
     ser::{Serialize, Serializer},
 };
 use typenum::Unsigned;
@@ -33,7 +36,49 @@ impl<T: Serialize, L: Unsigned> Serialize for SizedVec<T, L> {
 
 impl<'de, T: Deserialize<'de>, L: Unsigned> Deserialize<'de> for SizedVec<T, L> {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<SizedVec<T, L>, D::Error> {
-        Vec::<T>::deserialize(deserializer).map(|v| SizedVec::<T, L>(v, PhantomData))
+        let v = Vec::<T>::deserialize(deserializer)?;
+        let len = v.len();
+        Self::try_from(v).map_err(|_| {
+            de::Error::invalid_length(len, &format!("a vector of length {}", L::USIZE).as_str())
+        })
+    }
+}
+
+// the length is known, so the items are encoded without it, like borsh arrays
+impl<T: BorshSerialize, L: Unsigned> BorshSerialize for SizedVec<T, L> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        for item in self.iter() {
+            item.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: BorshDeserialize, L: Unsigned> BorshDeserialize for SizedVec<T, L> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, io::Error> {
+        let v = (0..L::USIZE)
+            .map(|_| T::deserialize(buf))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self(v, PhantomData))
+    }
+}
+
+// returns the vector back if its length is wrong
+impl<T, L: Unsigned> TryFrom<Vec<T>> for SizedVec<T, L> {
+    type Error = Vec<T>;
+
+    fn try_from(v: Vec<T>) -> Result<Self, Self::Error> {
+        if v.len() == L::USIZE {
+            Ok(Self(v, PhantomData))
+        } else {
+            Err(v)
+        }
+    }
+}
+
+impl<T: Default, L: Unsigned> Default for SizedVec<T, L> {
+    fn default() -> Self {
+        (0..L::USIZE).map(|_| T::default()).collect()
     }
 }
 
@@ -61,3 +106,33 @@ impl<T, I: SliceIndex<[T]>, L: Unsigned> IndexMut<I> for SizedVec<T, L> {
         IndexMut::index_mut(&mut *self.0, index)
     }
 }
+
+#[cfg(test)]
+mod sizedvec_test {
+    use borsh::BorshDeserialize;
+    use typenum::U3;
+
+    use super::*;
+
+    #[test]
+    fn test_sizedvec_length() {
+        let v = SizedVec::<u32, U3>::try_from(vec![1, 2, 3]).unwrap();
+        assert!(v.0 == vec![1, 2, 3]);
+        assert!(SizedVec::<u32, U3>::try_from(vec![1, 2]).unwrap_err() == vec![1, 2]);
+
+        assert!(
+            serde_json::from_str::<SizedVec<u32, U3>>("[1,2,3]")
+                .unwrap()
+                .0
+                == v.0
+        );
+        assert!(serde_json::from_str::<SizedVec<u32, U3>>("[1,2]").is_err());
+        assert!(serde_json::from_str::<SizedVec<u32, U3>>("[1,2,3,4]").is_err());
+
+        let bin = v.try_to_vec().unwrap();
+        assert!(bin.len() == 12);
+        assert!(SizedVec::<u32, U3>::try_from_slice(&bin).unwrap().0 == v.0);
+        assert!(SizedVec::<u32, U3>::try_from_slice(&bin[..8]).is_err());
+        assert!(SizedVec::<u32, U3>::try_from_slice(&[bin.clone(), bin].concat()).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use std::{error, fmt, io};
+
+use bellman::SynthesisError;
+
+#[derive(Debug)]
+pub enum Error {
+    AssignmentMissing,
+    UnsatisfiedConstraint(String),
+    MalformedParameters,
+    CircuitMismatch,
+    MissingDigest,
+    Io(io::Error),
+    Synthesis(SynthesisError),
+}
+
+impl From<SynthesisError> for Error {
+    fn from(e: SynthesisError) -> Self {
+        match e {
+            SynthesisError::AssignmentMissing => Error::AssignmentMissing,
+            SynthesisError::MalformedVerifyingKey => Error::MalformedParameters,
+            e => Error::Synthesis(e),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::AssignmentMissing => write!(f, "an assignment for a variable is missing"),
+            Error::UnsatisfiedConstraint(name) => write!(f, "constraint {} is not satisfied", name),
+            Error::MalformedParameters => {
+                write!(f, "parameters do not match the constraint system")
+            }
+            Error::CircuitMismatch => write!(f, "the keys were generated for a different circuit"),
+            Error::MissingDigest => write!(f, "the parameters have no circuit digest"),
+            Error::Io(e) => write!(f, "io error: {}", e),
+            Error::Synthesis(e) => write!(f, "synthesis error: {}", e),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Synthesis(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use pairing::bn256::Fq;
+use pairing::bn256::{Fq, Fr};
 
 use super::{verifier::TruncatedVerifyingKeyData, G1PointData, G2PointData};
+use crate::native::keccak::keccak256_shift;
 
 pub fn generate_sol_data(vk: &TruncatedVerifyingKeyData<Fq>) -> String {
     let tpl = String::from(
@@ -231,3 +232,30 @@ contract Verifier {
     tpl = tpl.replace("<%vk_ic_pts%>", &vi);
     tpl
 }
+
+// the public input of c_keccak256_inputs computed on chain from the n inputs, pass
+// InputHash.hash(inputs) as the only input of verifyProof
+pub fn generate_sol_input_hash(n: usize) -> String {
+    format!(
+        r###"library InputHash {{
+    function hash(uint256[{n}] memory inputs) internal pure returns (uint256) {{
+        return uint256(keccak256(abi.encodePacked(inputs))) >> {shift};
+    }}
+}}
+"###,
+        n = n,
+        shift = keccak256_shift::<Fr>()
+    )
+}
+
+#[cfg(test)]
+mod ethereum_test {
+    use super::*;
+
+    #[test]
+    fn test_sol_input_hash() {
+        let sol = generate_sol_input_hash(5);
+        assert!(sol.contains("function hash(uint256[5] memory inputs)"));
+        assert!(sol.contains("uint256(keccak256(abi.encodePacked(inputs))) >> 3;"));
+    }
+}
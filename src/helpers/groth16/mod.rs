@@ -14,6 +14,7 @@ use crate::{
     circuit::num::{CNum, Index},
     core::cs::ConstraintSystem,
     core::field::{AbstractField, Field, PrimeField},
+    error::Error,
     native::num::Num,
 };
 
@@ -270,6 +271,11 @@ pub struct Groth16CS<BE: bellman::pairing::Engine, BCS: bellman::ConstraintSyste
     pub naux: RefCell<usize>,
     pub ncons: RefCell<usize>,
     pub bcs: RefCell<BCS>,
+    pub namespace: RefCell<Vec<String>>,
+    pub lc_limit: RefCell<Option<usize>>,
+    // values of the allocated inputs, in order, without the constant one
+    pub inputs: RefCell<Vec<Option<Num<BE::Fr>>>>,
+    error: RefCell<Option<Error>>,
     be: std::marker::PhantomData<BE>,
 }
 
@@ -288,9 +294,25 @@ impl<BE: bellman::pairing::Engine, BCS: bellman::ConstraintSystem<BE>> Groth16CS
             naux: RefCell::new(0),
             ncons: RefCell::new(0),
             bcs: RefCell::new(cs),
+            namespace: RefCell::new(vec![]),
+            lc_limit: RefCell::new(None),
+            inputs: RefCell::new(vec![]),
+            error: RefCell::new(None),
             be: std::marker::PhantomData,
         }
     }
+
+    // the first error met during the synthesis, the synthesis itself goes on
+    pub fn take_error(&self) -> Option<Error> {
+        self.error.borrow_mut().take()
+    }
+
+    fn set_error(&self, e: Error) {
+        let mut error = self.error.borrow_mut();
+        if error.is_none() {
+            *error = Some(e);
+        }
+    }
 }
 
 impl<BE: bellman::pairing::Engine, BCS: bellman::ConstraintSystem<BE>> ConstraintSystem
@@ -298,25 +320,43 @@ impl<BE: bellman::pairing::Engine, BCS: bellman::ConstraintSystem<BE>> Constrain
 {
     type F = BE::Fr;
 
+    // on error bellman is not called again, the synthesis goes on with the constant one variable
+    // in place of the failed one, the proof is rejected by the recorded error
     fn alloc(&self, value: Option<Num<Self::F>>) -> Index {
-        let mut naux_ref = self.ninputs.borrow_mut();
+        self.try_alloc(value).unwrap_or_else(|e| {
+            self.set_error(e);
+            Index::Input(0)
+        })
+    }
+    fn alloc_input(&self, value: Option<Num<Self::F>>) -> Index {
+        self.try_alloc_input(value).unwrap_or_else(|e| {
+            self.set_error(e);
+            Index::Input(0)
+        })
+    }
+
+    fn try_alloc(&self, value: Option<Num<Self::F>>) -> Result<Index, Error> {
+        let mut naux_ref = self.naux.borrow_mut();
         let naux = *naux_ref;
-        *naux_ref += 1;
-        self.bcs
+        let index = self
+            .bcs
             .borrow_mut()
             .alloc(|| format!("a[{}]", naux), || value.grab())
-            .map(|e| unsafe { std::mem::transmute(e) })
-            .unwrap()
+            .map(|e| unsafe { std::mem::transmute(e) })?;
+        *naux_ref += 1;
+        Ok(index)
     }
-    fn alloc_input(&self, value: Option<Num<Self::F>>) -> Index {
+    fn try_alloc_input(&self, value: Option<Num<Self::F>>) -> Result<Index, Error> {
         let mut ninputs_ref = self.ninputs.borrow_mut();
         let ninputs = *ninputs_ref;
-        *ninputs_ref += 1;
-        self.bcs
+        let index = self
+            .bcs
             .borrow_mut()
             .alloc_input(|| format!("i[{}]", ninputs), || value.grab())
-            .map(|e| unsafe { std::mem::transmute(e) })
-            .unwrap()
+            .map(|e| unsafe { std::mem::transmute(e) })?;
+        *ninputs_ref += 1;
+        self.inputs.borrow_mut().push(value);
+        Ok(index)
     }
 
     fn enforce(&self, a: &CNum<Self>, b: &CNum<Self>, c: &CNum<Self>) {
@@ -333,6 +373,15 @@ impl<BE: bellman::pairing::Engine, BCS: bellman::ConstraintSystem<BE>> Constrain
         let mut ncons_ref = self.ncons.borrow_mut();
         let ncons = *ncons_ref;
         *ncons_ref += 1;
+        if let (Some(a_value), Some(b_value), Some(c_value)) = (a.value, b.value, c.value) {
+            if a_value * b_value != c_value {
+                self.set_error(Error::UnsatisfiedConstraint(format!(
+                    "{} at '{}'",
+                    ncons,
+                    self.namespace.borrow().join("/")
+                )));
+            }
+        }
         let a = into_bellman_lc(a);
         let b = into_bellman_lc(b);
         let c = into_bellman_lc(c);
@@ -340,4 +389,149 @@ impl<BE: bellman::pairing::Engine, BCS: bellman::ConstraintSystem<BE>> Constrain
             .borrow_mut()
             .enforce(|| format!("c[{}]", ncons), |_| a, |_| b, |_| c);
     }
+
+    fn push_namespace(&self, name: &str) {
+        self.namespace.borrow_mut().push(name.to_string());
+        let mut bcs = self.bcs.borrow_mut();
+        bellman::ConstraintSystem::push_namespace(bcs.get_root(), || name.to_string());
+    }
+
+    fn pop_namespace(&self) {
+        self.namespace.borrow_mut().pop();
+        let mut bcs = self.bcs.borrow_mut();
+        bellman::ConstraintSystem::pop_namespace(bcs.get_root());
+    }
+
+    fn lc_limit(&self) -> Option<usize> {
+        *self.lc_limit.borrow()
+    }
+
+    fn set_lc_limit(&self, limit: Option<usize>) {
+        *self.lc_limit.borrow_mut() = limit;
+    }
+}
+
+#[cfg(test)]
+mod groth16_test {
+    use bellman::pairing::bn256::{Bn256, Fr};
+    use bellman::{LinearCombination, Variable};
+
+    use super::*;
+    use crate::{
+        circuit::poseidon::c_poseidon, core::signal::Signal, native::poseidon::PoseidonParams,
+    };
+
+    #[derive(Default)]
+    struct NamesCS {
+        path: Vec<String>,
+        names: Vec<String>,
+        nvars: usize,
+        // fail on the missing values, as the prover does
+        strict: bool,
+    }
+
+    impl NamesCS {
+        fn record(&mut self, name: String) {
+            let mut path = self.path.clone();
+            path.push(name);
+            self.names.push(path.join("/"));
+        }
+    }
+
+    impl bellman::ConstraintSystem<Bn256> for NamesCS {
+        type Root = Self;
+
+        fn alloc<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+        where
+            F: FnOnce() -> Result<Fr, SynthesisError>,
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+        {
+            if self.strict {
+                f()?;
+            }
+            self.record(annotation().into());
+            self.nvars += 1;
+            Ok(Variable::new_unchecked(bellman::Index::Aux(self.nvars - 1)))
+        }
+
+        fn alloc_input<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+        where
+            F: FnOnce() -> Result<Fr, SynthesisError>,
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+        {
+            self.alloc(annotation, f)
+        }
+
+        fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, _: LA, _: LB, _: LC)
+        where
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+            LA: FnOnce(LinearCombination<Bn256>) -> LinearCombination<Bn256>,
+            LB: FnOnce(LinearCombination<Bn256>) -> LinearCombination<Bn256>,
+            LC: FnOnce(LinearCombination<Bn256>) -> LinearCombination<Bn256>,
+        {
+            self.record(annotation().into());
+        }
+
+        fn push_namespace<NR, N>(&mut self, name_fn: N)
+        where
+            NR: Into<String>,
+            N: FnOnce() -> NR,
+        {
+            self.path.push(name_fn().into());
+        }
+
+        fn pop_namespace(&mut self) {
+            self.path.pop();
+        }
+
+        fn get_root(&mut self) -> &mut Self::Root {
+            self
+        }
+    }
+
+    #[test]
+    fn test_groth16_namespace() {
+        let mut bcs = NamesCS::default();
+        {
+            let cs =
+                &Groth16CS::<Bn256, _>::new(bellman::ConstraintSystem::namespace(&mut bcs, || {
+                    "root"
+                }));
+            let poseidon_params = PoseidonParams::<Fr>::new(2, 8, 53);
+            let x = CNum::alloc(cs, None);
+            x.inputize();
+            c_poseidon(&[x], &poseidon_params);
+        }
+
+        assert!(bcs.path.is_empty());
+        assert!(bcs.names[0] == "root/a[0]");
+        assert!(bcs.names[1] == "root/i[1]");
+        assert!(bcs.names[2] == "root/c[0]");
+        assert!(bcs.names[3..]
+            .iter()
+            .all(|n| n.starts_with("root/poseidon/")));
+    }
+
+    #[test]
+    fn test_groth16_alloc_error() {
+        let mut bcs = NamesCS {
+            strict: true,
+            ..NamesCS::default()
+        };
+        {
+            let cs =
+                &Groth16CS::<Bn256, _>::new(bellman::ConstraintSystem::namespace(&mut bcs, || {
+                    "root"
+                }));
+            CNum::alloc(cs, Some(&num!(3)));
+            let y = CNum::alloc(cs, None);
+            CNum::alloc(cs, Some(&num!(5)));
+            assert!(y.lc.iter().all(|(i, _)| *i == Index::Input(0)));
+            assert!(matches!(cs.take_error(), Some(Error::AssignmentMissing)));
+        }
+        assert!(bcs.nvars == 2);
+    }
 }
@@ -60,6 +60,8 @@ impl<T: Field> BorshDeserialize for ProofData<T> {
     }
 }
 
+// the layout is read by the NEAR contracts, so the digest is not stored and is None on reading,
+// keep the digest of the key next to it to check it with TruncatedVerifyingKey::check_digest
 impl<T: Field> BorshSerialize for TruncatedVerifyingKeyData<T> {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
         self.alpha_g1.serialize(writer)?;
@@ -78,6 +80,7 @@ impl<T: Field> BorshDeserialize for TruncatedVerifyingKeyData<T> {
             gamma_g2: G2PointData::deserialize(buf)?,
             delta_g2: G2PointData::deserialize(buf)?,
             ic: <_>::deserialize(buf)?,
+            digest: None,
         })
     }
 }
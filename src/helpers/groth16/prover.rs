@@ -1,4 +1,7 @@
-use std::io::{self, Read, Write};
+use std::{
+    cell::RefCell,
+    io::{self, Read, Write},
+};
 
 use bellman::{self, SynthesisError};
 use pairing::{bls12_381, bn256, Engine};
@@ -8,8 +11,11 @@ use crate::{
     core::cs::Circuit,
     core::field::Field,
     core::osrng::OsRng,
+    core::r1cs::CircuitDigest,
+    error::Error,
     helpers::groth16::Groth16CS,
     helpers::groth16::{G1PointData, G2PointData},
+    native::num::Num,
 };
 
 pub use bellman::groth16::Parameters;
@@ -80,34 +86,164 @@ impl Proof<bn256::Bn256> {
     }
 }
 
-struct HelperCircuit<'a, C: Circuit>(pub &'a C);
+struct HelperCircuit<'a, BE: Engine, C: Circuit> {
+    circuit: &'a C,
+    params: Option<&'a Parameters<BE>>,
+    error: &'a RefCell<Option<Error>>,
+    inputs: &'a RefCell<Vec<Option<Num<BE::Fr>>>>,
+}
+
+impl<'a, BE: Engine, C: Circuit> HelperCircuit<'a, BE, C> {
+    fn fail(&self, e: Error) -> SynthesisError {
+        let res = match e {
+            Error::AssignmentMissing => SynthesisError::AssignmentMissing,
+            Error::MalformedParameters => SynthesisError::MalformedVerifyingKey,
+            _ => SynthesisError::Unsatisfiable,
+        };
+        *self.error.borrow_mut() = Some(e);
+        res
+    }
+}
 
 impl<'a, BE: bellman::pairing::Engine, C: Circuit<F = BE::Fr>> bellman::Circuit<BE>
-    for HelperCircuit<'a, C>
+    for HelperCircuit<'a, BE, C>
 {
     fn synthesize<CS: bellman::ConstraintSystem<BE>>(
         self,
         cs: &mut CS,
     ) -> Result<(), SynthesisError> {
-        let ref cs = Groth16CS::new(cs.namespace(|| "root"));
-        self.0.synthesize(cs);
+        let cs = Groth16CS::new(cs.namespace(|| "root"));
+        self.circuit.synthesize(&cs);
+        if let Some(e) = cs.take_error() {
+            return Err(self.fail(e));
+        }
+        // bellman does not check the sizes of the parameters against the circuit
+        if let Some(params) = self.params {
+            if params.vk.ic.len() != *cs.ninputs.borrow() || params.l.len() != *cs.naux.borrow() {
+                return Err(self.fail(Error::MalformedParameters));
+            }
+        }
+        *self.inputs.borrow_mut() = cs.inputs.into_inner();
         Ok(())
     }
 }
 
-pub fn generate_keys<BE: bellman::pairing::Engine, C: Circuit<F = BE::Fr> + Default>(
-) -> bellman::groth16::Parameters<BE> {
-    let ref mut rng = OsRng::new();
+pub fn try_generate_keys<BE: bellman::pairing::Engine, C: Circuit<F = BE::Fr> + Default>(
+) -> Result<Parameters<BE>, Error> {
+    let rng = &mut OsRng::new();
     let c = C::default();
-    bellman::groth16::generate_random_parameters(HelperCircuit(&c), rng).unwrap()
+    let error = RefCell::new(None);
+    let inputs = RefCell::new(vec![]);
+    let circuit = HelperCircuit {
+        circuit: &c,
+        params: None,
+        error: &error,
+        inputs: &inputs,
+    };
+    bellman::groth16::generate_random_parameters(circuit, rng)
+        .map_err(|e| error.into_inner().unwrap_or_else(|| e.into()))
+}
+
+pub fn generate_keys<BE: bellman::pairing::Engine, C: Circuit<F = BE::Fr> + Default>(
+) -> Parameters<BE> {
+    try_generate_keys::<BE, C>().unwrap()
+}
+
+pub fn try_prove<BE: bellman::pairing::Engine, C: Circuit<F = BE::Fr>>(
+    c: &C,
+    params: &Parameters<BE>,
+) -> Result<Proof<BE>, Error> {
+    try_prove_with_inputs(c, params).map(|(proof, _)| proof)
 }
 
 pub fn prove<BE: bellman::pairing::Engine, C: Circuit<F = BE::Fr>>(
     c: &C,
-    params: &bellman::groth16::Parameters<BE>,
+    params: &Parameters<BE>,
 ) -> Proof<BE> {
-    let ref mut rng = OsRng::new();
-    Proof(bellman::groth16::create_random_proof(HelperCircuit(c), params, rng).unwrap())
+    try_prove(c, params).unwrap()
+}
+
+// returns the proof and the public inputs, recorded during the same synthesis
+pub fn try_prove_with_inputs<BE: bellman::pairing::Engine, C: Circuit<F = BE::Fr>>(
+    c: &C,
+    params: &Parameters<BE>,
+) -> Result<(Proof<BE>, Vec<Num<BE::Fr>>), Error> {
+    let rng = &mut OsRng::new();
+    let error = RefCell::new(None);
+    let inputs = RefCell::new(vec![]);
+    let circuit = HelperCircuit {
+        circuit: c,
+        params: Some(params),
+        error: &error,
+        inputs: &inputs,
+    };
+    let proof = bellman::groth16::create_random_proof(circuit, params, rng)
+        .map_err(|e| error.into_inner().unwrap_or_else(|| e.into()))?;
+    let inputs = inputs
+        .into_inner()
+        .into_iter()
+        .collect::<Option<_>>()
+        .ok_or(Error::AssignmentMissing)?;
+    Ok((Proof(proof), inputs))
+}
+
+pub fn prove_with_inputs<BE: bellman::pairing::Engine, C: Circuit<F = BE::Fr>>(
+    c: &C,
+    params: &Parameters<BE>,
+) -> (Proof<BE>, Vec<Num<BE::Fr>>) {
+    try_prove_with_inputs(c, params).unwrap()
+}
+
+const DIGEST_MAGIC: &[u8; 6] = b"digest";
+
+// the digest is appended to the parameters, bellman ignores it on reading
+pub fn write_params<BE: bellman::pairing::Engine, W: Write>(
+    params: &Parameters<BE>,
+    digest: &CircuitDigest,
+    mut writer: W,
+) -> io::Result<()> {
+    params.write(&mut writer)?;
+    writer.write_all(DIGEST_MAGIC)?;
+    writer.write_all(&digest.0)
+}
+
+// the parameters without the digest are rejected, see read_params_legacy
+pub fn read_params_checked<BE: bellman::pairing::Engine, R: Read>(
+    reader: R,
+    digest: &CircuitDigest,
+) -> Result<Parameters<BE>, Error> {
+    read_params(reader, digest, false)
+}
+
+// accepts the parameters written before the digest was introduced, they are not checked
+pub fn read_params_legacy<BE: bellman::pairing::Engine, R: Read>(
+    reader: R,
+    digest: &CircuitDigest,
+) -> Result<Parameters<BE>, Error> {
+    read_params(reader, digest, true)
+}
+
+fn read_params<BE: bellman::pairing::Engine, R: Read>(
+    mut reader: R,
+    digest: &CircuitDigest,
+    allow_legacy: bool,
+) -> Result<Parameters<BE>, Error> {
+    let params = Parameters::read(&mut reader, false)?;
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest)?;
+    if rest.is_empty() {
+        if allow_legacy {
+            return Ok(params);
+        }
+        return Err(Error::MissingDigest);
+    }
+    if rest.len() != DIGEST_MAGIC.len() + digest.0.len() || !rest.starts_with(DIGEST_MAGIC) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Wrong digest").into());
+    }
+    if rest[DIGEST_MAGIC.len()..] != digest.0 {
+        return Err(Error::CircuitMismatch);
+    }
+    Ok(params)
 }
 
 #[cfg(test)]
@@ -119,10 +255,12 @@ mod bellman_test {
     use crate::{
         circuit::num::CNum,
         circuit::poseidon::c_poseidon,
-        core::cs::{ConstraintSystem, TestCS},
+        core::cs::ConstraintSystem,
         core::field::Field,
         core::signal::Signal,
-        helpers::groth16::verifier::{truncate_verifying_key, verify},
+        helpers::groth16::verifier::{
+            truncate_verifying_key, verify, verify_checked, TruncatedVerifyingKey,
+        },
         native::num::Num,
         native::poseidon::PoseidonParams,
     };
@@ -143,12 +281,6 @@ mod bellman_test {
             let image_computed = c_poseidon([preimage].as_ref(), poseidon_params);
             (&image - &image_computed).assert_zero();
         }
-
-        fn get_inputs(&self) -> Option<Vec<Num<Self::F>>> {
-            let ref cs = TestCS::new();
-            let image = CNum::alloc(cs, self.image.as_ref());
-            image.linearize().iter().map(|e| e.get_value()).collect()
-        }
     }
 
     #[test]
@@ -162,10 +294,119 @@ mod bellman_test {
             image: Some(image),
             preimage: Some(preimage),
         };
-        let proof = prove(&c, &params);
+        let (proof, inputs) = prove_with_inputs(&c, &params);
+        assert!(inputs == vec![image]);
+        assert!(inputs == c.get_inputs().unwrap());
 
         let pvk = truncate_verifying_key(&params.vk);
-        let res = verify(&pvk, &proof, [image.into_inner()].as_ref()).unwrap();
+        let res = verify(&pvk, &proof, &[inputs[0].into_inner()]).unwrap();
         assert!(res, "proof should be valid");
     }
+
+    #[derive(Default)]
+    struct CheckSquare<F: Field> {
+        square: Option<Num<F>>,
+        root: Option<Num<F>>,
+    }
+
+    impl<F: Field> Circuit for CheckSquare<F> {
+        type F = F;
+        fn synthesize<CS: ConstraintSystem<F = F>>(&self, cs: &CS) {
+            let square = CNum::alloc(cs, self.square.as_ref());
+            square.inputize();
+            let root = CNum::alloc(cs, self.root.as_ref());
+            (&root * &root - &square).assert_zero();
+        }
+    }
+
+    #[test]
+    fn test_try_prove() {
+        let mut rng = thread_rng();
+        let params = try_generate_keys::<Bn256, CheckPreimageKnowledge<Fr>>().unwrap();
+        let preimage: Num<Fr> = rng.gen();
+        let poseidon_params = &PoseidonParams::<Fr>::new(2, 8, 53);
+        let image = crate::native::poseidon::poseidon([preimage].as_ref(), poseidon_params);
+
+        let c = CheckPreimageKnowledge {
+            image: Some(image),
+            preimage: Some(preimage + Num::one()),
+        };
+        match try_prove(&c, &params) {
+            Err(Error::UnsatisfiedConstraint(name)) => assert!(name.ends_with(" at ''")),
+            _ => panic!("unsatisfied constraint should be reported"),
+        }
+
+        let c = CheckPreimageKnowledge {
+            image: Some(image),
+            preimage: None,
+        };
+        assert!(matches!(
+            try_prove(&c, &params),
+            Err(Error::AssignmentMissing)
+        ));
+
+        let c = CheckSquare {
+            square: Some(preimage * preimage),
+            root: Some(preimage),
+        };
+        assert!(matches!(
+            try_prove(&c, &params),
+            Err(Error::MalformedParameters)
+        ));
+
+        let c = CheckPreimageKnowledge {
+            image: Some(image),
+            preimage: Some(preimage),
+        };
+        assert!(try_prove(&c, &params).is_ok());
+    }
+
+    #[test]
+    fn test_params_digest() {
+        let params = generate_keys::<Bn256, CheckSquare<Fr>>();
+        let digest = CircuitDigest::from_circuit(&CheckSquare::<Fr>::default());
+        let other_digest = CircuitDigest::from_circuit(&CheckPreimageKnowledge::<Fr>::default());
+
+        let mut legacy = vec![];
+        params.write(&mut legacy).unwrap();
+        assert!(matches!(
+            read_params_checked::<Bn256, _>(&legacy[..], &digest),
+            Err(Error::MissingDigest)
+        ));
+        assert!(read_params_legacy::<Bn256, _>(&legacy[..], &digest).is_ok());
+
+        let mut data = vec![];
+        write_params(&params, &digest, &mut data).unwrap();
+        assert!(Parameters::<Bn256>::read(&data[..], false).is_ok());
+        assert!(read_params_checked::<Bn256, _>(&data[..], &digest).is_ok());
+        assert!(matches!(
+            read_params_checked::<Bn256, _>(&data[..], &other_digest),
+            Err(Error::CircuitMismatch)
+        ));
+        assert!(matches!(
+            read_params_legacy::<Bn256, _>(&data[..], &other_digest),
+            Err(Error::CircuitMismatch)
+        ));
+
+        let mut tvk = truncate_verifying_key(&params.vk);
+        assert!(tvk.check_digest(&other_digest).is_ok());
+        tvk.digest = Some(digest);
+        let json = serde_json::to_string(&tvk.into_data()).unwrap();
+        let tvk = TruncatedVerifyingKey::<Bn256>::from_data(&serde_json::from_str(&json).unwrap());
+        assert!(tvk.digest == Some(digest));
+        assert!(tvk.check_digest(&digest).is_ok());
+        assert!(matches!(
+            tvk.check_digest(&other_digest),
+            Err(Error::CircuitMismatch)
+        ));
+
+        let c = CheckSquare {
+            square: Some(num!(4)),
+            root: Some(num!(2)),
+        };
+        let (proof, inputs) = prove_with_inputs(&c, &params);
+        assert!(inputs == vec![num!(4)]);
+        assert!(verify_checked(&tvk, &digest, &proof, &[num!(4).into_inner()]).unwrap());
+        assert!(verify_checked(&tvk, &other_digest, &proof, &[num!(4).into_inner()]).is_err());
+    }
 }
@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     core::field::{AbstractField, Field, PrimeField},
+    core::r1cs::CircuitDigest,
+    error::Error,
     helpers::groth16::{prover::Proof, G1PointData, G2PointData},
 };
 
@@ -20,6 +22,9 @@ pub struct TruncatedVerifyingKeyData<F: Field> {
     pub gamma_g2: G2PointData<F>,
     pub delta_g2: G2PointData<F>,
     pub ic: Vec<G1PointData<F>>,
+    // not included in the borsh encoding used by the NEAR contracts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<CircuitDigest>,
 }
 
 #[derive(Clone)]
@@ -29,6 +34,9 @@ pub struct TruncatedVerifyingKey<E: Engine> {
     pub gamma_g2: E::G2Affine,
     pub delta_g2: E::G2Affine,
     pub ic: Vec<E::G1Affine>,
+    // digest of the circuit the key was generated for, it is not stored in the binary format
+    // used by the blockchains and is None for the keys read from it
+    pub digest: Option<CircuitDigest>,
 }
 
 impl TruncatedVerifyingKey<bn256::Bn256> {
@@ -39,6 +47,7 @@ impl TruncatedVerifyingKey<bn256::Bn256> {
             gamma_g2: G2PointData::from(self.gamma_g2),
             delta_g2: G2PointData::from(self.delta_g2),
             ic: self.ic.iter().map(|e| G1PointData::from(*e)).collect(),
+            digest: self.digest,
         }
     }
 
@@ -53,6 +62,7 @@ impl TruncatedVerifyingKey<bn256::Bn256> {
                 .iter()
                 .map(|p| Into::<bn256::G1Affine>::into(*p))
                 .collect(),
+            digest: vk.digest,
         }
     }
 }
@@ -65,6 +75,7 @@ impl TruncatedVerifyingKey<bls12_381::Bls12> {
             gamma_g2: G2PointData::from(self.gamma_g2),
             delta_g2: G2PointData::from(self.delta_g2),
             ic: self.ic.iter().map(|e| G1PointData::from(*e)).collect(),
+            digest: self.digest,
         }
     }
 
@@ -79,11 +90,13 @@ impl TruncatedVerifyingKey<bls12_381::Bls12> {
                 .iter()
                 .map(|p| Into::<bls12_381::G1Affine>::into(*p))
                 .collect(),
+            digest: vk.digest,
         }
     }
 }
 
 impl<E: Engine> TruncatedVerifyingKey<E> {
+    // the format of the Waves contracts, the digest is not stored, as in the NEAR borsh format
     pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
         writer.write_all(self.alpha_g1.into_compressed().as_ref())?;
         writer.write_all(self.beta_g2.into_compressed().as_ref())?;
@@ -184,8 +197,17 @@ impl<E: Engine> TruncatedVerifyingKey<E> {
             gamma_g2: gamma_g2,
             delta_g2: delta_g2,
             ic: ic.clone(),
+            digest: None,
         })
     }
+
+    // keys without the digest are not checked
+    pub fn check_digest(&self, digest: &CircuitDigest) -> Result<(), Error> {
+        match self.digest {
+            Some(d) if d != *digest => Err(Error::CircuitMismatch),
+            _ => Ok(()),
+        }
+    }
 }
 
 pub fn truncate_verifying_key<E: Engine>(vk: &VerifyingKey<E>) -> TruncatedVerifyingKey<E> {
@@ -195,9 +217,20 @@ pub fn truncate_verifying_key<E: Engine>(vk: &VerifyingKey<E>) -> TruncatedVerif
         gamma_g2: vk.gamma_g2.clone(),
         delta_g2: vk.delta_g2.clone(),
         ic: vk.ic.clone(),
+        digest: None,
     }
 }
 
+pub fn verify_checked<E: Engine>(
+    tvk: &TruncatedVerifyingKey<E>,
+    digest: &CircuitDigest,
+    proof: &Proof<E>,
+    public_inputs: &[E::Fr],
+) -> Result<bool, Error> {
+    tvk.check_digest(digest)?;
+    Ok(verify(tvk, proof, public_inputs)?)
+}
+
 pub fn verify<'a, E: Engine>(
     tvk: &'a TruncatedVerifyingKey<E>,
     proof: &Proof<E>,
@@ -1,9 +1,13 @@
+// the paths generated by fawkes-crypto_derive start with fawkes_crypto
+extern crate self as fawkes_crypto;
+
 #[macro_use]
 pub mod macros;
 
 pub mod circuit;
 pub mod constants;
 pub mod core;
+pub mod error;
 pub mod helpers;
 pub mod native;
 
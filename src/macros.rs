@@ -231,13 +231,14 @@ macro_rules! groth16_ethereum_bindings {
 
             use pairing::bn256::{Bn256, Fq, Fr};
             use $crate::{
-                core::{
-                    cs::{Circuit, TestCS},
-                    signal::Signal,
-                },
+                core::{cs::Circuit, r1cs::CircuitDigest, signal::Signal},
+                error::Error,
                 helpers::groth16::{
                     ethereum::generate_sol_data,
-                    prover::{generate_keys, prove, Parameters, Proof},
+                    prover::{
+                        generate_keys, prove_with_inputs, read_params_checked, read_params_legacy,
+                        write_params, Proof,
+                    },
                     verifier::{
                         truncate_verifying_key, verify, TruncatedVerifyingKey,
                         TruncatedVerifyingKeyData,
@@ -344,12 +345,6 @@ macro_rules! groth16_ethereum_bindings {
                     $cir_main(&p, &s, &$cir_params);
                     p.inputize();
                 }
-
-                fn get_inputs(&self) -> Option<Vec<Num<Self::F>>> {
-                    let ref cs = TestCS::new();
-                    let p = $ccir_pub::alloc(cs, self.p.as_ref());
-                    p.linearize().iter().map(|e| e.get_value()).collect()
-                }
             }
 
             impl Default for CircuitObject {
@@ -360,10 +355,11 @@ macro_rules! groth16_ethereum_bindings {
 
             fn cli_setup(o: SetupOpts) {
                 let params = generate_keys::<Bn256, CircuitObject>();
-                let vk_data_str =
-                    serde_json::to_string_pretty(&truncate_verifying_key(&params.vk).into_data())
-                        .unwrap();
-                params.write(File::create(o.params).unwrap()).unwrap();
+                let digest = CircuitDigest::from_circuit(&CircuitObject::default());
+                let mut tvk = truncate_verifying_key(&params.vk);
+                tvk.digest = Some(digest);
+                let vk_data_str = serde_json::to_string_pretty(&tvk.into_data()).unwrap();
+                write_params(&params, &digest, File::create(o.params).unwrap()).unwrap();
                 std::fs::write(o.vk, &vk_data_str.into_bytes()).unwrap();
                 println!("setup OK");
             }
@@ -374,7 +370,7 @@ macro_rules! groth16_ethereum_bindings {
                 let sol_str = generate_sol_data(&vk);
                 File::create(o.solidity)
                     .unwrap()
-                    .write(&sol_str.into_bytes())
+                    .write_all(&sol_str.into_bytes())
                     .unwrap();
                 println!("solidity verifier generated")
             }
@@ -387,6 +383,8 @@ macro_rules! groth16_ethereum_bindings {
                 let vk = TruncatedVerifyingKey::<Bn256>::from_data(
                     &serde_json::from_str(&vk_str).unwrap(),
                 );
+                vk.check_digest(&CircuitDigest::from_circuit(&CircuitObject::default()))
+                    .unwrap();
                 let proof = Proof::<Bn256>::from_data(&serde_json::from_str(&proof_str).unwrap());
                 let public_inputs = serde_json::from_str::<Vec<Num<Fr>>>(&public_inputs_str)
                     .unwrap()
@@ -408,8 +406,18 @@ macro_rules! groth16_ethereum_bindings {
             }
 
             fn cli_prove(o: ProveOpts) {
-                let params =
-                    Parameters::<Bn256>::read(File::open(o.params).unwrap(), false).unwrap();
+                let digest = CircuitDigest::from_circuit(&CircuitObject::default());
+                let data = std::fs::read(&o.params).unwrap();
+                let params = match read_params_checked::<Bn256, _>(&data[..], &digest) {
+                    Err(Error::MissingDigest) => {
+                        eprintln!(
+                            "warning: {} has no circuit digest, it is not checked",
+                            o.params
+                        );
+                        read_params_legacy::<Bn256, _>(&data[..], &digest).unwrap()
+                    }
+                    params => params.unwrap(),
+                };
                 let object_str = std::fs::read_to_string(o.object).unwrap();
 
                 let (p, s) = serde_json::from_str::<($cir_pub, $cir_sec)>(&object_str).unwrap();
@@ -417,8 +425,7 @@ macro_rules! groth16_ethereum_bindings {
                     p: Some(p),
                     s: Some(s),
                 };
-                let proof = prove(&c, &params);
-                let inputs = c.get_inputs().unwrap();
+                let (proof, inputs) = prove_with_inputs(&c, &params);
 
                 let proof_str = serde_json::to_string_pretty(&proof.into_data()).unwrap();
                 let inputs_str = serde_json::to_string_pretty(&inputs).unwrap();
@@ -455,12 +462,13 @@ macro_rules! groth16_waves_bindings {
 
             use pairing::bls12_381::{Bls12, Fr};
             use $crate::{
-                core::{
-                    cs::{Circuit, TestCS},
-                    signal::Signal,
-                },
+                core::{cs::Circuit, r1cs::CircuitDigest, signal::Signal},
+                error::Error,
                 helpers::groth16::{
-                    prover::{generate_keys, prove, Parameters, Proof},
+                    prover::{
+                        generate_keys, prove_with_inputs, read_params_checked, read_params_legacy,
+                        write_params, Proof,
+                    },
                     verifier::{truncate_verifying_key, verify, TruncatedVerifyingKey},
                 },
                 native::num::Num,
@@ -589,12 +597,6 @@ macro_rules! groth16_waves_bindings {
                     $cir_main(&p, &s, &$cir_params);
                     p.inputize();
                 }
-
-                fn get_inputs(&self) -> Option<Vec<Num<Self::F>>> {
-                    let ref cs = TestCS::new();
-                    let p = $ccir_pub::alloc(cs, self.p.as_ref());
-                    p.linearize().iter().map(|e| e.get_value()).collect()
-                }
             }
 
             impl Default for CircuitObject {
@@ -610,7 +612,8 @@ macro_rules! groth16_waves_bindings {
                     .write(&mut tvk_data)
                     .unwrap();
                 std::fs::write(o.vk, &base64::encode(tvk_data.get_ref()).into_bytes()).unwrap();
-                params.write(File::create(o.params).unwrap()).unwrap();
+                let digest = CircuitDigest::from_circuit(&CircuitObject::default());
+                write_params(&params, &digest, File::create(o.params).unwrap()).unwrap();
 
                 println!("setup OK");
             }
@@ -644,8 +647,18 @@ macro_rules! groth16_waves_bindings {
             }
 
             fn cli_prove(o: ProveOpts) {
-                let params =
-                    Parameters::<Bls12>::read(File::open(o.params).unwrap(), false).unwrap();
+                let digest = CircuitDigest::from_circuit(&CircuitObject::default());
+                let data = std::fs::read(&o.params).unwrap();
+                let params = match read_params_checked::<Bls12, _>(&data[..], &digest) {
+                    Err(Error::MissingDigest) => {
+                        eprintln!(
+                            "warning: {} has no circuit digest, it is not checked",
+                            o.params
+                        );
+                        read_params_legacy::<Bls12, _>(&data[..], &digest).unwrap()
+                    }
+                    params => params.unwrap(),
+                };
                 let object_str = std::fs::read_to_string(o.object).unwrap();
 
                 let (p, s) = serde_json::from_str::<($cir_pub, $cir_sec)>(&object_str).unwrap();
@@ -653,8 +666,7 @@ macro_rules! groth16_waves_bindings {
                     p: Some(p),
                     s: Some(s),
                 };
-                let proof = prove(&c, &params);
-                let inputs = c.get_inputs().unwrap();
+                let (proof, inputs) = prove_with_inputs(&c, &params);
 
                 let mut proof_data = Cursor::new(Vec::<u8>::new());
                 proof.write(&mut proof_data).unwrap();
@@ -693,12 +705,13 @@ macro_rules! groth16_waves_bindings_bn256 {
 
             use pairing::bn256::{Bn256, Fr};
             use $crate::{
-                core::{
-                    cs::{Circuit, TestCS},
-                    signal::Signal,
-                },
+                core::{cs::Circuit, r1cs::CircuitDigest, signal::Signal},
+                error::Error,
                 helpers::groth16::{
-                    prover::{generate_keys, prove, Parameters, Proof},
+                    prover::{
+                        generate_keys, prove_with_inputs, read_params_checked, read_params_legacy,
+                        write_params, Proof,
+                    },
                     verifier::{truncate_verifying_key, verify, TruncatedVerifyingKey},
                 },
                 native::num::Num,
@@ -827,12 +840,6 @@ macro_rules! groth16_waves_bindings_bn256 {
                     $cir_main(&p, &s, &$cir_params);
                     p.inputize();
                 }
-
-                fn get_inputs(&self) -> Option<Vec<Num<Self::F>>> {
-                    let ref cs = TestCS::new();
-                    let p = $ccir_pub::alloc(cs, self.p.as_ref());
-                    p.linearize().iter().map(|e| e.get_value()).collect()
-                }
             }
 
             impl Default for CircuitObject {
@@ -848,7 +855,8 @@ macro_rules! groth16_waves_bindings_bn256 {
                     .write(&mut tvk_data)
                     .unwrap();
                 std::fs::write(o.vk, &base64::encode(tvk_data.get_ref()).into_bytes()).unwrap();
-                params.write(File::create(o.params).unwrap()).unwrap();
+                let digest = CircuitDigest::from_circuit(&CircuitObject::default());
+                write_params(&params, &digest, File::create(o.params).unwrap()).unwrap();
 
                 println!("setup OK");
             }
@@ -882,8 +890,18 @@ macro_rules! groth16_waves_bindings_bn256 {
             }
 
             fn cli_prove(o: ProveOpts) {
-                let params =
-                    Parameters::<Bn256>::read(File::open(o.params).unwrap(), false).unwrap();
+                let digest = CircuitDigest::from_circuit(&CircuitObject::default());
+                let data = std::fs::read(&o.params).unwrap();
+                let params = match read_params_checked::<Bn256, _>(&data[..], &digest) {
+                    Err(Error::MissingDigest) => {
+                        eprintln!(
+                            "warning: {} has no circuit digest, it is not checked",
+                            o.params
+                        );
+                        read_params_legacy::<Bn256, _>(&data[..], &digest).unwrap()
+                    }
+                    params => params.unwrap(),
+                };
                 let object_str = std::fs::read_to_string(o.object).unwrap();
 
                 let (p, s) = serde_json::from_str::<($cir_pub, $cir_sec)>(&object_str).unwrap();
@@ -891,8 +909,7 @@ macro_rules! groth16_waves_bindings_bn256 {
                     p: Some(p),
                     s: Some(s),
                 };
-                let proof = prove(&c, &params);
-                let inputs = c.get_inputs().unwrap();
+                let (proof, inputs) = prove_with_inputs(&c, &params);
 
                 let mut proof_data = Cursor::new(Vec::<u8>::new());
                 proof.write(&mut proof_data).unwrap();
@@ -932,13 +949,14 @@ macro_rules! groth16_near_bindings {
 
             use pairing::bn256::{Bn256, Fr};
             use $crate::{
-                core::{
-                    cs::{Circuit, TestCS},
-                    signal::Signal,
-                },
+                core::{cs::Circuit, r1cs::CircuitDigest, signal::Signal},
+                error::Error,
                 helpers::groth16::{
                     near,
-                    prover::{generate_keys, prove, Parameters, Proof},
+                    prover::{
+                        generate_keys, prove_with_inputs, read_params_checked, read_params_legacy,
+                        write_params, Proof,
+                    },
                     verifier::{truncate_verifying_key, verify, TruncatedVerifyingKey},
                 },
                 native::num::Num,
@@ -1067,12 +1085,6 @@ macro_rules! groth16_near_bindings {
                     $cir_main(&p, &s, &$cir_params);
                     p.inputize();
                 }
-
-                fn get_inputs(&self) -> Option<Vec<Num<Self::F>>> {
-                    let ref cs = TestCS::new();
-                    let p = $ccir_pub::alloc(cs, self.p.as_ref());
-                    p.linearize().iter().map(|e| e.get_value()).collect()
-                }
             }
 
             impl Default for CircuitObject {
@@ -1086,7 +1098,8 @@ macro_rules! groth16_near_bindings {
                 let tvk = truncate_verifying_key(&params.vk);
                 let mut tvk_data = tvk.into_data().try_to_vec().unwrap();
                 std::fs::write(o.vk, &base64::encode(&tvk_data).into_bytes()).unwrap();
-                params.write(File::create(o.params).unwrap()).unwrap();
+                let digest = CircuitDigest::from_circuit(&CircuitObject::default());
+                write_params(&params, &digest, File::create(o.params).unwrap()).unwrap();
 
                 println!("setup OK");
             }
@@ -1124,8 +1137,18 @@ macro_rules! groth16_near_bindings {
             }
 
             fn cli_prove(o: ProveOpts) {
-                let params =
-                    Parameters::<Bn256>::read(File::open(o.params).unwrap(), false).unwrap();
+                let digest = CircuitDigest::from_circuit(&CircuitObject::default());
+                let data = std::fs::read(&o.params).unwrap();
+                let params = match read_params_checked::<Bn256, _>(&data[..], &digest) {
+                    Err(Error::MissingDigest) => {
+                        eprintln!(
+                            "warning: {} has no circuit digest, it is not checked",
+                            o.params
+                        );
+                        read_params_legacy::<Bn256, _>(&data[..], &digest).unwrap()
+                    }
+                    params => params.unwrap(),
+                };
                 let object_str = std::fs::read_to_string(o.object).unwrap();
 
                 let (p, s) = serde_json::from_str::<($cir_pub, $cir_sec)>(&object_str).unwrap();
@@ -1133,8 +1156,7 @@ macro_rules! groth16_near_bindings {
                     p: Some(p),
                     s: Some(s),
                 };
-                let proof = prove(&c, &params);
-                let inputs = c.get_inputs().unwrap();
+                let (proof, inputs) = prove_with_inputs(&c, &params);
 
                 let proof_data = proof.into_data().try_to_vec().unwrap();
                 let inputs_data = inputs.try_to_vec().unwrap();
@@ -0,0 +1,169 @@
+use num::bigint::BigUint;
+
+use crate::{core::field::Field, native::num::Num};
+
+pub const KECCAK256_RATE: usize = 136;
+
+pub const KECCAK_RC: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+// rotation offsets of the lanes, the lane (x, y) has index x + 5*y
+pub const KECCAK_ROTATIONS: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+// index of the lane, where the lane (x, y) goes in the pi step
+#[inline]
+pub fn keccak_pi(x: usize, y: usize) -> usize {
+    y + 5 * ((2 * x + 3 * y) % 5)
+}
+
+pub fn keccak_f(a: &mut [u64; 25]) {
+    for rc in KECCAK_RC.iter() {
+        let c = (0..5)
+            .map(|x| a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20])
+            .collect::<Vec<_>>();
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+
+        let mut b = [0u64; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[keccak_pi(x, y)] = a[x + 5 * y].rotate_left(KECCAK_ROTATIONS[x + 5 * y]);
+            }
+        }
+
+        for x in 0..5 {
+            for y in 0..5 {
+                a[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+        a[0] ^= rc;
+    }
+}
+
+// message with the 0x01 byte, zeros and the 0x80 bit, the length is a multiple of the rate
+pub fn keccak256_pad(data: &[u8]) -> Vec<u8> {
+    let mut res = data.to_vec();
+    res.push(0x01);
+    res.resize((data.len() / KECCAK256_RATE + 1) * KECCAK256_RATE, 0);
+    *res.last_mut().unwrap() |= 0x80;
+    res
+}
+
+// the original Keccak padding, as keccak256 in Solidity
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut state = [0u64; 25];
+    for block in keccak256_pad(data).chunks(KECCAK256_RATE) {
+        for (s, lane) in state.iter_mut().zip(block.chunks(8)) {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(lane);
+            *s ^= u64::from_le_bytes(bytes);
+        }
+        keccak_f(&mut state);
+    }
+    let mut res = [0u8; 32];
+    for (i, s) in state.iter().take(4).enumerate() {
+        res[8 * i..8 * i + 8].copy_from_slice(&s.to_le_bytes());
+    }
+    res
+}
+
+// the value packed as uint256 by abi.encodePacked in Solidity
+pub fn num_to_bytes_be<F: Field>(n: Num<F>) -> [u8; 32] {
+    let bytes = Into::<BigUint>::into(n).to_bytes_be();
+    let mut res = [0u8; 32];
+    res[32 - bytes.len()..].copy_from_slice(&bytes);
+    res
+}
+
+// number of the lowest bits of the digest dropped by keccak256_to_num
+pub fn keccak256_shift<F: Field>() -> usize {
+    assert!(F::NUM_BITS <= 256, "the field should fit into uint256");
+    257 - F::NUM_BITS as usize
+}
+
+// uint256(h) >> shift in Solidity, the result is less than 2^(NUM_BITS - 1), so it fits into
+// the field
+pub fn keccak256_to_num<F: Field>(h: &[u8; 32]) -> Num<F> {
+    Num::from(BigUint::from_bytes_be(h) >> keccak256_shift::<F>())
+}
+
+// keccak256(abi.encodePacked(inputs)) reduced into the field, the public input of
+// c_keccak256_inputs
+pub fn keccak256_inputs<F: Field>(inputs: &[Num<F>]) -> Num<F> {
+    let data = inputs
+        .iter()
+        .flat_map(|&n| num_to_bytes_be(n).to_vec())
+        .collect::<Vec<_>>();
+    keccak256_to_num(&keccak256(&data))
+}
+
+#[cfg(test)]
+mod keccak_test {
+    use bellman::pairing::bn256::Fr;
+
+    use super::*;
+
+    fn to_hex(data: &[u8]) -> String {
+        data.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_keccak256() {
+        assert!(
+            to_hex(&keccak256(b""))
+                == "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert!(
+            to_hex(&keccak256(b"abc"))
+                == "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+        // two blocks
+        let data = (0..200).map(|i| i as u8).collect::<Vec<_>>();
+        assert!(
+            to_hex(&keccak256(&data))
+                == "bfb0aa97863e797943cf7c33bb7e880bb4543f3d2703c0923c6901c2af57b890"
+        );
+        assert!(keccak256(&[0u8; 135]) != keccak256(&[0u8; 136]));
+    }
+
+    #[test]
+    fn test_keccak256_to_num() {
+        let mut bytes = [0u8; 32];
+        bytes[31] = 5;
+        assert!(num_to_bytes_be(Num::<Fr>::from(5)) == bytes);
+
+        let max = (BigUint::from(1u64) << 253) - BigUint::from(1u64);
+        assert!(keccak256_shift::<Fr>() == 3);
+        assert!(keccak256_to_num::<Fr>(&[0xff; 32]) == Num::from(max));
+    }
+}
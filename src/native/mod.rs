@@ -1,7 +1,9 @@
 pub mod ecc;
 pub mod eddsaposeidon;
+pub mod keccak;
 pub mod num;
 pub mod poseidon;
+pub mod sha256;
 
 pub mod bls12_381;
 pub mod bn256;
@@ -240,6 +240,12 @@ impl<T: Field> Into<u64> for Num<T> {
     }
 }
 
+impl<T: Field> Default for Num<T> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
 impl<T: Field> From<u64> for Num<T> {
     fn from(n: u64) -> Self {
         let mut repr = T::zero().into_raw_repr();
@@ -1,3 +1,6 @@
+use std::io::{self, Write};
+
+use borsh::{BorshDeserialize, BorshSerialize};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use typenum::Unsigned;
@@ -90,6 +93,19 @@ pub fn poseidon_with_salt<F: Field>(
     poseidon(&inputs, params)
 }
 
+// hash of any number of inputs, the number of inputs goes first, so the inputs of different
+// lengths are not confused. Every call absorbs the previous hash and up to t-1 inputs
+pub fn poseidon_chain<F: Field>(inputs: &[Num<F>], params: &PoseidonParams<F>) -> Num<F> {
+    assert!(params.t > 1, "t should be greater than 1");
+    let mut res = Num::from(inputs.len() as u64);
+    for i in (0..inputs.len().max(1)).step_by(params.t - 1) {
+        let mut state = vec![res];
+        state.extend_from_slice(&inputs[i..(i + params.t - 1).min(inputs.len())]);
+        res = poseidon(&state, params);
+    }
+    res
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(bound(serialize = "", deserialize = ""))]
 pub struct MerkleProof<F: Field, L: Unsigned> {
@@ -97,6 +113,22 @@ pub struct MerkleProof<F: Field, L: Unsigned> {
     pub path: SizedVec<bool, L>,
 }
 
+impl<F: Field, L: Unsigned> BorshSerialize for MerkleProof<F, L> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        BorshSerialize::serialize(&self.sibling, writer)?;
+        BorshSerialize::serialize(&self.path, writer)
+    }
+}
+
+impl<F: Field, L: Unsigned> BorshDeserialize for MerkleProof<F, L> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, io::Error> {
+        Ok(Self {
+            sibling: BorshDeserialize::deserialize(buf)?,
+            path: BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
 pub fn poseidon_merkle_proof_root<F: Field, L: Unsigned>(
     leaf: Num<F>,
     proof: &MerkleProof<F, L>,
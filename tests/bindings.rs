@@ -0,0 +1,70 @@
+// the bindings macros are only expanded by the downstream crates, so they are compiled here
+use fawkes_crypto::{
+    circuit::num::CNum, core::cs::ConstraintSystem, core::field::Field, core::signal::Signal,
+    groth16_ethereum_bindings, groth16_near_bindings, groth16_waves_bindings,
+    groth16_waves_bindings_bn256, native::num::Num, num,
+};
+
+pub struct Params;
+
+static PARAMS: Params = Params;
+
+fn c_main<'a, CS: ConstraintSystem>(p: &CNum<'a, CS>, s: &CNum<'a, CS>, _: &Params) {
+    (s * s).assert_eq(p);
+}
+
+fn test_data<F: Field>() -> (Num<F>, Num<F>) {
+    (num!(4), num!(2))
+}
+
+groth16_ethereum_bindings!(
+    ethereum,
+    Num<Fr>,
+    CNum,
+    Num<Fr>,
+    CNum,
+    PARAMS,
+    c_main,
+    test_data
+);
+groth16_waves_bindings!(
+    waves,
+    Num<Fr>,
+    CNum,
+    Num<Fr>,
+    CNum,
+    PARAMS,
+    c_main,
+    test_data
+);
+groth16_waves_bindings_bn256!(
+    waves_bn256,
+    Num<Fr>,
+    CNum,
+    Num<Fr>,
+    CNum,
+    PARAMS,
+    c_main,
+    test_data
+);
+groth16_near_bindings!(
+    near,
+    Num<Fr>,
+    CNum,
+    Num<Fr>,
+    CNum,
+    PARAMS,
+    c_main,
+    test_data
+);
+
+// the expansions are checked by the compiler, the entry points are public
+#[test]
+fn test_bindings() {
+    let _: [fn(); 4] = [
+        ethereum::cli_main,
+        waves::cli_main,
+        waves_bn256::cli_main,
+        near::cli_main,
+    ];
+}